@@ -0,0 +1,521 @@
+use crate::models::{Attachment, AttachmentCleanup, AttachmentFilter, AttachmentInfo, AttachmentReference, AttachmentSort};
+use crate::storage::FileStorageManager;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const ATTACHMENTS_DIR: &str = "attachments";
+// Older versions saved pasted images here
+const IMAGES_DIR: &str = "images";
+// The index records local modification times, so it stays on this machine
+const INDEX_DIR_NAME: &str = "attachment-index";
+const INDEX_FILE_NAME: &str = "attachments.json";
+// Local to this machine, like version history; removed attachments keep
+// their relative path here
+const TRASH_DIR_NAME: &str = "attachment-trash";
+
+// Stores must not interleave their index updates
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+// Every file under attachments/ and images/, keyed by the path notes link to
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AttachmentIndex {
+    #[serde(default)]
+    files: BTreeMap<String, IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    hash: String,
+    size: u64,
+    modified: DateTime<Utc>,
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dimensions: Option<(u32, u32)>,
+    #[serde(default)]
+    created: DateTime<Utc>,
+}
+
+pub struct AttachmentsManager {
+    storage: FileStorageManager,
+}
+
+impl AttachmentsManager {
+    pub fn new(storage: FileStorageManager) -> Self {
+        Self { storage }
+    }
+
+    /// Saves `data` under attachments/ and returns the path to link from a note.
+    /// A file with identical content is stored once and its path reused; other
+    /// files never overwrite an existing attachment.
+    pub async fn save_attachment(&self, file_name: &str, data: &[u8]) -> Result<String> {
+        let hash = hash_bytes(data);
+        let _guard = INDEX_LOCK.lock().unwrap();
+        let mut index = self.load_index()?;
+        if let Some(path) = index.files.iter().find(|(_, entry)| entry.hash == hash).map(|(path, _)| path) {
+            return Ok(path.clone());
+        }
+
+        let dir = self.attachments_directory();
+        fs::create_dir_all(&dir).context("Failed to create attachments directory")?;
+        let name = unique_file_name(&dir, file_name);
+        let file_path = dir.join(&name);
+        fs::write(&file_path, data).context("Failed to write attachment")?;
+
+        let path = format!("{}/{}", ATTACHMENTS_DIR, name);
+        index.files.insert(path.clone(), index_entry(&file_path, data, None)?);
+        self.save_index(&index)?;
+        Ok(path)
+    }
+
+    /// All attachments in the vault, filtered and sorted (by path unless asked otherwise).
+    pub async fn list_attachments(&self, filter: &AttachmentFilter) -> Result<Vec<Attachment>> {
+        let index = {
+            let _guard = INDEX_LOCK.lock().unwrap();
+            self.load_index()?
+        };
+        let references = self.note_references()?;
+
+        let mut attachments: Vec<Attachment> = index.files.iter()
+            .map(|(path, entry)| attachment(path, entry, references.get(path).map_or(0, Vec::len)))
+            .filter(|a| matches_filter(a, filter))
+            .collect();
+
+        if let Some(sort_by) = filter.sort_by {
+            attachments.sort_by(|a, b| match sort_by {
+                AttachmentSort::Name => a.file_name.to_lowercase().cmp(&b.file_name.to_lowercase()),
+                AttachmentSort::Size => a.size.cmp(&b.size),
+                AttachmentSort::Created => a.created_at.cmp(&b.created_at),
+                AttachmentSort::References => a.reference_count.cmp(&b.reference_count),
+            });
+        }
+        if filter.descending {
+            attachments.reverse();
+        }
+        Ok(attachments)
+    }
+
+    /// An attachment's details and the notes linking to it, trashed ones included.
+    pub async fn get_attachment_info(&self, path: &str) -> Result<Option<AttachmentInfo>> {
+        let index = {
+            let _guard = INDEX_LOCK.lock().unwrap();
+            self.load_index()?
+        };
+        let entry = match index.files.get(path) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let referenced_by = self.note_references()?.remove(path).unwrap_or_default();
+        Ok(Some(AttachmentInfo {
+            attachment: attachment(path, entry, referenced_by.len()),
+            referenced_by,
+        }))
+    }
+
+    /// Attachments no note links to, from front matter or body; notes in the
+    /// trash still count. Unless `dry_run`, they are moved to the attachment trash.
+    pub async fn cleanup_unreferenced_attachments(&self, dry_run: bool) -> Result<AttachmentCleanup> {
+        let _guard = INDEX_LOCK.lock().unwrap();
+        let mut index = self.load_index()?;
+        let references = self.note_references()?;
+        let orphans: Vec<String> = index.files.keys()
+            .filter(|path| !references.contains_key(*path))
+            .cloned()
+            .collect();
+        let bytes = orphans.iter().map(|path| index.files[path].size).sum();
+
+        if !dry_run && !orphans.is_empty() {
+            let trash = self.storage.local_directory(TRASH_DIR_NAME)?;
+            for path in &orphans {
+                let target = trash.join(path);
+                let dir = target.parent().unwrap_or(&trash);
+                fs::create_dir_all(dir).context("Failed to create attachment trash")?;
+                let name = unique_file_name(dir, path);
+                fs::rename(self.storage.notes_directory.join(path), dir.join(name))
+                    .with_context(|| format!("Failed to move {} to the attachment trash", path))?;
+                index.files.remove(path);
+            }
+            self.save_index(&index)?;
+        }
+
+        Ok(AttachmentCleanup {
+            attachments: orphans,
+            bytes,
+            applied: !dry_run,
+        })
+    }
+
+    // Notes linking to each attachment path, from front matter and body links.
+    // Bodies of encrypted notes cannot be read, so only their front matter
+    // counts; encrypting or editing one records its links there.
+    fn note_references(&self) -> Result<HashMap<String, Vec<AttachmentReference>>> {
+        let mut references: HashMap<String, Vec<AttachmentReference>> = HashMap::new();
+        for file in self.storage.scan_existing_files()? {
+            if let Ok((metadata, content)) = self.storage.parse_note(&file.name) {
+                let mut paths: Vec<String> = metadata.attachments.iter()
+                    .map(|path| attachment_path(path).unwrap_or_else(|| path.clone()))
+                    .collect();
+                if !metadata.encrypted {
+                    paths.extend(extract_attachment_references(&content));
+                }
+                paths.sort();
+                paths.dedup();
+                for path in paths {
+                    references.entry(path).or_default().push(AttachmentReference {
+                        note_id: metadata.id.clone(),
+                        title: metadata.title.clone(),
+                        deleted: metadata.deleted,
+                    });
+                }
+            }
+        }
+        Ok(references)
+    }
+
+    fn attachments_directory(&self) -> PathBuf {
+        self.storage.notes_directory.join(ATTACHMENTS_DIR)
+    }
+
+    fn index_path(&self) -> Result<PathBuf> {
+        self.storage.local_file(INDEX_DIR_NAME, INDEX_FILE_NAME)
+    }
+
+    // Loads the index and brings it in line with the files on disk, so files
+    // added or edited outside the app are read and deleted ones dropped.
+    // Callers hold INDEX_LOCK.
+    fn load_index(&self) -> Result<AttachmentIndex> {
+        let path = self.index_path()?;
+        let mut index: AttachmentIndex = if path.exists() {
+            let content = fs::read_to_string(&path).context("Failed to read attachment index")?;
+            // A damaged index is rebuilt from the files
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            AttachmentIndex::default()
+        };
+
+        let mut files = Vec::new();
+        for dir in [ATTACHMENTS_DIR, IMAGES_DIR] {
+            collect_files(&self.storage.notes_directory.join(dir), &mut files)?;
+        }
+        let mut current = BTreeMap::new();
+        let mut changed = false;
+        for file_path in files {
+            let relative = file_path.strip_prefix(&self.storage.notes_directory).unwrap_or(&file_path);
+            let key = relative.to_string_lossy().replace('\\', "/");
+            let size = fs::metadata(&file_path)?.len();
+            let modified = modified_time(&file_path)?;
+            let entry = match index.files.remove(&key) {
+                Some(entry) if entry.size == size && entry.modified == modified && !entry.mime_type.is_empty() => entry,
+                previous => {
+                    changed = true;
+                    let created = previous.filter(|e| !e.mime_type.is_empty()).map(|e| e.created);
+                    index_entry(&file_path, &fs::read(&file_path)?, created)?
+                }
+            };
+            current.insert(key, entry);
+        }
+        changed |= !index.files.is_empty();
+        index.files = current;
+        if changed {
+            self.save_index(&index)?;
+        }
+        Ok(index)
+    }
+
+    fn save_index(&self, index: &AttachmentIndex) -> Result<()> {
+        let path = self.index_path()?;
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, serde_json::to_string_pretty(index)?).context("Failed to write attachment index")?;
+        fs::rename(&temp_path, &path).context("Failed to write attachment index")
+    }
+}
+
+/// Attachment paths linked from a note body under `attachments/` or `images/`:
+/// markdown links and images, inline or as reference definitions, and HTML
+/// `<img>` tags.
+pub fn extract_attachment_references(content: &str) -> Vec<String> {
+    let mut attachments = Vec::new();
+    
+    let patterns = [
+        // ![alt](attachments/filename "title"), [text](<attachments/file name>)
+        r"\]\(\s*(<[^>]+>|[^)\s]+)",
+        // [id]: attachments/filename
+        r"(?m)^ {0,3}\[[^\]]+\]:\s*(<[^>]+>|\S+)",
+        // <img src="images/filename">
+        r#"(?i)<img\b[^>]*?\ssrc\s*=\s*(?:"([^"]+)"|'([^']+)'|([^\s"'>]+))"#,
+    ];
+    
+    for pattern in &patterns {
+        if let Ok(re) = regex::Regex::new(pattern) {
+            for cap in re.captures_iter(content) {
+                if let Some(path) = cap.iter().skip(1).flatten().next().and_then(|m| attachment_path(m.as_str())) {
+                    attachments.push(path);
+                }
+            }
+        }
+    }
+    
+    // Remove duplicates
+    attachments.sort();
+    attachments.dedup();
+    
+    attachments
+}
+
+// A link target as a path relative to the notes directory, if it points into
+// one of the attachment folders
+fn attachment_path(target: &str) -> Option<String> {
+    let target = target.trim().trim_start_matches('<').trim_end_matches('>');
+    let path = percent_decode(target).replace('\\', "/");
+    let path = path.trim_start_matches("./").trim_start_matches('/');
+    [ATTACHMENTS_DIR, IMAGES_DIR].iter()
+        .any(|dir| path.strip_prefix(dir).is_some_and(|rest| rest.len() > 1 && rest.starts_with('/')))
+        .then(|| path.to_string())
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn matches_filter(attachment: &Attachment, filter: &AttachmentFilter) -> bool {
+    if let Some(query) = &filter.query {
+        if !attachment.path.to_lowercase().contains(&query.to_lowercase()) {
+            return false;
+        }
+    }
+    if let Some(mime_type) = &filter.mime_type {
+        if !attachment.mime_type.starts_with(mime_type.as_str()) {
+            return false;
+        }
+    }
+    match filter.referenced {
+        Some(referenced) => (attachment.reference_count > 0) == referenced,
+        None => true,
+    }
+}
+
+fn attachment(path: &str, entry: &IndexEntry, reference_count: usize) -> Attachment {
+    Attachment {
+        path: path.to_string(),
+        file_name: path.rsplit('/').next().unwrap_or(path).to_string(),
+        size: entry.size,
+        mime_type: entry.mime_type.clone(),
+        hash: entry.hash.clone(),
+        width: entry.dimensions.map(|(width, _)| width),
+        height: entry.dimensions.map(|(_, height)| height),
+        created_at: entry.created,
+        reference_count,
+    }
+}
+
+// Reads everything the index keeps about a file. `created` carries over from
+// an earlier entry for the same path.
+fn index_entry(path: &Path, data: &[u8], created: Option<DateTime<Utc>>) -> Result<IndexEntry> {
+    let metadata = fs::metadata(path)?;
+    let mime_type = mime_guess::from_path(path).first_or_octet_stream().to_string();
+    let dimensions = if mime_type.starts_with("image/") {
+        imagesize::blob_size(data).ok().map(|size| (size.width as u32, size.height as u32))
+    } else {
+        None
+    };
+    let created = match created {
+        Some(created) => created,
+        None => metadata.created().or_else(|_| metadata.modified())?.into(),
+    };
+    Ok(IndexEntry {
+        hash: hash_bytes(data),
+        size: data.len() as u64,
+        modified: metadata.modified()?.into(),
+        mime_type,
+        dimensions,
+        created,
+    })
+}
+
+fn hash_bytes(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn modified_time(path: &Path) -> Result<DateTime<Utc>> {
+    Ok(fs::metadata(path)?.modified()?.into())
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else if path.is_file() {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+// `name`, or `stem_1.ext`, `stem_2.ext`, ... if taken. Any directory part of
+// `name` is dropped.
+fn unique_file_name(dir: &Path, name: &str) -> String {
+    let name = Path::new(name).file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .filter(|n| !n.starts_with('.'))
+        .unwrap_or_else(|| "attachment".to_string());
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem.to_string(), format!(".{}", extension)),
+        _ => (name.clone(), String::new()),
+    };
+
+    let mut candidate = name;
+    let mut counter = 1;
+    while dir.join(&candidate).exists() {
+        candidate = format!("{}_{}{}", stem, counter, extension);
+        counter += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+
+    #[tokio::test]
+    async fn test_identical_files_are_stored_once() {
+        let vault = test_vault();
+        let manager = vault.attachments();
+
+        // Linked from notes before the index existed
+        let dir = vault.path().join(ATTACHMENTS_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("diagram.png"), b"old diagram").unwrap();
+
+        let first = manager.save_attachment("image.png", b"screenshot one").await.unwrap();
+        let second = manager.save_attachment("image.png", b"screenshot two").await.unwrap();
+        assert_eq!((first.as_str(), second.as_str()), ("attachments/image.png", "attachments/image_1.png"));
+        assert_eq!(fs::read(dir.join("image.png")).unwrap(), b"screenshot one");
+
+        assert_eq!(manager.save_attachment("copy.png", b"screenshot one").await.unwrap(), first);
+        assert_eq!(manager.save_attachment("other.png", b"old diagram").await.unwrap(), "attachments/diagram.png");
+        assert_eq!(manager.save_attachment("../../escape.txt", b"text").await.unwrap(), "attachments/escape.txt");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 4);
+
+        // Kept out of git sync, since mtimes differ between clones
+        let vault = vault.path().join(crate::vault::VAULT_DIR_NAME);
+        assert!(!vault.join(INDEX_FILE_NAME).exists());
+        assert!(vault.join(INDEX_DIR_NAME).join(INDEX_FILE_NAME).exists());
+        assert!(vault.join(INDEX_DIR_NAME).join(".gitignore").exists());
+    }
+
+    #[tokio::test]
+    async fn test_registry_metadata_and_references() {
+        let vault = test_vault();
+        let notes = &vault.notes;
+        let manager = vault.attachments();
+
+        // PNG signature and IHDR chunk of a 3x2 image
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend([0, 0, 0, 3, 0, 0, 0, 2, 8, 6, 0, 0, 0]);
+        let image = manager.save_attachment("chart.png", &png).await.unwrap();
+        let report = manager.save_attachment("report.pdf", b"%PDF-1.4 quarterly").await.unwrap();
+
+        let note = vault.create_note("Review", Some(&format!("![chart]({})", image)), &[]).await;
+        notes.add_attachment_to_note(&note.id, &report).await.unwrap();
+        let trashed = vault.create_note("Draft", Some(&format!("[old]({})", image)), &[]).await;
+        notes.delete_note(&trashed.id).await.unwrap();
+        manager.save_attachment("unused.txt", b"nothing links here").await.unwrap();
+
+        let info = manager.get_attachment_info(&image).await.unwrap().unwrap();
+        assert_eq!(info.attachment.mime_type, "image/png");
+        assert_eq!((info.attachment.width, info.attachment.height), (Some(3), Some(2)));
+        assert_eq!(info.attachment.hash, hash_bytes(&png));
+        assert_eq!(info.referenced_by.len(), 2);
+        assert!(info.referenced_by.iter().any(|r| r.note_id == trashed.id && r.deleted));
+        assert!(manager.get_attachment_info("attachments/missing.png").await.unwrap().is_none());
+
+        let by_size = manager.list_attachments(&AttachmentFilter {
+            sort_by: Some(AttachmentSort::Size),
+            descending: true,
+            ..AttachmentFilter::default()
+        }).await.unwrap();
+        assert_eq!(by_size[0].path, image);
+        let images = manager.list_attachments(&AttachmentFilter { mime_type: Some("image/".to_string()), ..AttachmentFilter::default() }).await.unwrap();
+        assert_eq!(images.len(), 1);
+        let unused = manager.list_attachments(&AttachmentFilter { referenced: Some(false), ..AttachmentFilter::default() }).await.unwrap();
+        assert_eq!(unused.iter().map(|a| a.file_name.as_str()).collect::<Vec<_>>(), vec!["unused.txt"]);
+    }
+
+    #[test]
+    fn test_references_from_links_definitions_and_html() {
+        let content = "![shot](attachments/image_1.png \"Screenshot\")\n\
+            [spec](<attachments/q3 spec.pdf>) and [site](https://example.com/attachments/x.png)\n\
+            <p><img class=\"wide\" src='images/old%20chart.png'></p>\n\
+            [ref]: ./attachments/ref.png\n";
+        assert_eq!(extract_attachment_references(content), vec![
+            "attachments/image_1.png",
+            "attachments/q3 spec.pdf",
+            "attachments/ref.png",
+            "images/old chart.png",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_keeps_body_references_and_trashes_orphans() {
+        let vault = test_vault();
+        let notes = &vault.notes;
+        let manager = vault.attachments();
+
+        let pasted = manager.save_attachment("image.png", b"pasted").await.unwrap();
+        let orphan = manager.save_attachment("old.png", b"orphan").await.unwrap();
+        let images = vault.path().join(IMAGES_DIR);
+        fs::create_dir_all(&images).unwrap();
+        fs::write(images.join("legacy.png"), b"legacy").unwrap();
+
+        let note = vault.create_note("Pasted", Some(&format!("![]({})\n<img src=\"images/legacy.png\">", pasted)), &[]).await;
+        notes.delete_note(&note.id).await.unwrap();
+        let secret = manager.save_attachment("scan.png", b"secret").await.unwrap();
+        let private = vault.create_note("Private", Some(&format!("![scan]({})", secret)), &[]).await;
+        let private = notes.encrypt_note(&private.id, "correct horse").await.unwrap().unwrap();
+        assert_eq!(private.attachments, vec![secret.clone()]);
+
+        let preview = manager.cleanup_unreferenced_attachments(true).await.unwrap();
+        assert_eq!((preview.attachments.clone(), preview.bytes, preview.applied), (vec![orphan.clone()], 6, false));
+        assert!(vault.path().join(&orphan).exists());
+
+        manager.cleanup_unreferenced_attachments(false).await.unwrap();
+        assert!(!vault.path().join(&orphan).exists());
+        assert!(vault.path().join(&pasted).exists());
+        assert!(vault.path().join(&secret).exists());
+        let trash = vault.path().join(crate::vault::VAULT_DIR_NAME).join(TRASH_DIR_NAME);
+        assert_eq!(fs::read(trash.join(&orphan)).unwrap(), b"orphan");
+        assert_eq!(fs::read_to_string(trash.join(".gitignore")).unwrap(), "*\n");
+        assert!(manager.cleanup_unreferenced_attachments(true).await.unwrap().attachments.is_empty());
+    }
+}
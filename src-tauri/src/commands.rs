@@ -1,4 +1,5 @@
-use crate::{NotesManager, TagsManager, ConfigManager};
+use crate::{NotesManager, TagsManager, AttachmentsManager, ConfigManager};
+use crate::notes::NoteLockedError;
 use crate::models::*;
 use crate::{log_debug, log_info, log_warn, log_error};
 use std::sync::{Arc, Mutex};
@@ -11,9 +12,36 @@ use std::fs;
 pub struct AppState {
     pub notes_manager: Arc<NotesManager>,
     pub tags_manager: Arc<TagsManager>,
+    pub attachments_manager: Arc<AttachmentsManager>,
     pub config_manager: Arc<Mutex<ConfigManager>>,
 }
 
+/// Error returned by commands that can hit a locked note. Serialized with a
+/// `kind` tag so the UI can offer to unlock instead of showing a bare message.
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CommandError {
+    NoteLocked { note_id: String, message: String },
+    Failed { message: String },
+}
+
+impl From<anyhow::Error> for CommandError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast_ref::<NoteLockedError>() {
+            Some(locked) => CommandError::NoteLocked { note_id: locked.note_id.clone(), message: e.to_string() },
+            None => CommandError::Failed { message: e.to_string() },
+        }
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::NoteLocked { message, .. } | CommandError::Failed { message } => f.write_str(message),
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn is_setup_required(state: State<'_, Arc<AppState>>) -> Result<bool, String> {
     log_info!("Checking if setup is required");
@@ -23,9 +51,10 @@ pub async fn is_setup_required(state: State<'_, Arc<AppState>>) -> Result<bool,
 }
 
 #[tauri::command]
-pub async fn get_all_notes(state: State<'_, Arc<AppState>>) -> Result<Vec<Note>, String> {
+pub async fn get_all_notes(state: State<'_, Arc<AppState>>, sort: Option<NoteSort>) -> Result<Vec<Note>, String> {
     log_info!("Getting all notes");
     let result = state.notes_manager.get_all_notes().await
+        .and_then(|notes| sorted_notes(&state, notes, sort, None))
         .map_err(|e| e.to_string());
     
     if let Ok(notes) = &result {
@@ -36,9 +65,10 @@ pub async fn get_all_notes(state: State<'_, Arc<AppState>>) -> Result<Vec<Note>,
 }
 
 #[tauri::command]
-pub async fn get_favorites(state: State<'_, Arc<AppState>>) -> Result<Vec<Note>, String> {
+pub async fn get_favorites(state: State<'_, Arc<AppState>>, sort: Option<NoteSort>) -> Result<Vec<Note>, String> {
     log_info!("Getting favorite notes");
     let result = state.notes_manager.get_favorite_notes().await
+        .and_then(|notes| sorted_notes(&state, notes, sort, None))
         .map_err(|e| e.to_string());
     
     if let Ok(notes) = &result {
@@ -49,9 +79,10 @@ pub async fn get_favorites(state: State<'_, Arc<AppState>>) -> Result<Vec<Note>,
 }
 
 #[tauri::command]
-pub async fn get_untagged(state: State<'_, Arc<AppState>>) -> Result<Vec<Note>, String> {
+pub async fn get_untagged(state: State<'_, Arc<AppState>>, sort: Option<NoteSort>) -> Result<Vec<Note>, String> {
     log_info!("Getting untagged notes");
     let result = state.notes_manager.get_untagged_notes().await
+        .and_then(|notes| sorted_notes(&state, notes, sort, None))
         .map_err(|e| e.to_string());
     
     if let Ok(notes) = &result {
@@ -62,312 +93,1224 @@ pub async fn get_untagged(state: State<'_, Arc<AppState>>) -> Result<Vec<Note>,
 }
 
 #[tauri::command]
-pub async fn get_trash(state: State<'_, Arc<AppState>>) -> Result<Vec<Note>, String> {
+pub async fn get_trash(state: State<'_, Arc<AppState>>, sort: Option<NoteSort>) -> Result<Vec<Note>, String> {
     log_info!("Getting trash notes");
-    let result = state.notes_manager.get_trash().await
+    let result = state.notes_manager.get_trash(sort.unwrap_or_default()).await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(notes) = &result {
+        log_debug!("Found {} trash notes", notes.len());
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn get_notes_by_tag(state: State<'_, Arc<AppState>>, tag_name: String, include_descendants: Option<bool>, sort: Option<NoteSort>) -> Result<Vec<Note>, String> {
+    log_info!("Getting notes by tag: {}", tag_name);
+    let result = state.notes_manager.get_notes_by_tag(&tag_name, include_descendants.unwrap_or(false)).await
+        .and_then(|notes| sorted_notes(&state, notes, sort, Some(&tag_name)))
+        .map_err(|e| e.to_string());
+    
+    if let Ok(notes) = &result {
+        log_debug!("Found {} notes with tag '{}'", notes.len(), tag_name);
+    }
+    
+    result
+}
+
+// Lists come back newest first with pins on top; re-sort only when asked
+fn sorted_notes(state: &AppState, mut notes: Vec<Note>, sort: Option<NoteSort>, tag: Option<&str>) -> Result<Vec<Note>> {
+    if let Some(sort) = sort {
+        state.notes_manager.sort_notes(&mut notes, sort, tag)?;
+    }
+    Ok(notes)
+}
+
+#[tauri::command]
+pub async fn get_all_tags(state: State<'_, Arc<AppState>>) -> Result<Vec<Tag>, String> {
+    log_info!("Getting all tags");
+    let result = state.tags_manager.get_all_tags().await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(tags) = &result {
+        log_debug!("Found {} tags", tags.len());
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn get_note(state: State<'_, Arc<AppState>>, id: String) -> Result<Option<Note>, String> {
+    log_info!("Getting note with id: {}", id);
+    let result = state.notes_manager.get_note(&id).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(Some(note)) => log_debug!("Found note with title: {}", note.title),
+        Ok(None) => log_debug!("Note with id '{}' not found", id),
+        Err(e) => log_error!("Error getting note {}: {}", id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn create_note(state: State<'_, Arc<AppState>>, request: CreateNoteRequest) -> Result<Note, String> {
+    log_info!("Creating new note with title: {}", request.title);
+    let result = state.notes_manager.create_note(request).await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(note) = &result {
+        log_debug!("Successfully created note with id: {}", note.id);
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn update_note(state: State<'_, Arc<AppState>>, request: UpdateNoteRequest) -> Result<Option<Note>, CommandError> {
+    log_info!("Updating note with id: {}", request.id);
+    let result = state.notes_manager.update_note(request).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(note)) => log_debug!("Successfully updated note with title: {}", note.title),
+        Ok(None) => log_debug!("Note with id not found for update"),
+        Err(e) => log_error!("Error updating note: {}", e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn delete_note(state: State<'_, Arc<AppState>>, id: String) -> Result<bool, CommandError> {
+    log_info!("Deleting note with id: {}", id);
+    let result = state.notes_manager.delete_note(&id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(true) => log_debug!("Successfully deleted note {}", id),
+        Ok(false) => log_debug!("Note {} not found for deletion", id),
+        Err(e) => log_error!("Error deleting note {}: {}", id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn search_notes(state: State<'_, Arc<AppState>>, request: SearchRequest) -> Result<Vec<Note>, String> {
+    log_info!("Searching notes with query: '{}', tag_filter: {:?}", request.query, request.tag_filter);
+    
+    let result = state.notes_manager.search_notes(&request.query, request.tag_filter.as_deref(), request.sort).await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(notes) = &result {
+        log_debug!("Found {} notes matching search criteria", notes.len());
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn restore_note(state: State<'_, Arc<AppState>>, id: String) -> Result<bool, CommandError> {
+    log_info!("Restoring note with id: {}", id);
+    let result = state.notes_manager.restore_note(&id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(true) => log_debug!("Successfully restored note {}", id),
+        Ok(false) => log_debug!("Note {} not found for restoration", id),
+        Err(e) => log_error!("Error restoring note {}: {}", id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn permanently_delete_note(state: State<'_, Arc<AppState>>, id: String) -> Result<bool, CommandError> {
+    log_info!("Permanently deleting note with id: {}", id);
+    let result = state.notes_manager.permanently_delete_note(&id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(true) => log_debug!("Successfully permanently deleted note {}", id),
+        Ok(false) => log_debug!("Note {} not found for permanent deletion", id),
+        Err(e) => log_error!("Error permanently deleting note {}: {}", id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn show_directory_dialog() -> Result<Option<String>, String> {
+    log_info!("Showing directory dialog");
+    use tauri::api::dialog::FileDialogBuilder;
+    
+    let (tx, rx) = std::sync::mpsc::channel();
+    
+    FileDialogBuilder::new()
+        .set_title("Select Data Directory")
+        .pick_folder(move |path_buf| {
+            let _ = tx.send(path_buf.map(|p| p.to_string_lossy().to_string()));
+        });
+    
+    let result = rx.recv().map_err(|e| e.to_string());
+    
+    if let Ok(Some(path)) = &result {
+        log_debug!("Selected directory: {}", path);
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn update_data_directory(state: State<'_, Arc<AppState>>, path: String) -> Result<bool, String> {
+    log_info!("Updating data directory to: {}", path);
+    use std::path::PathBuf;
+    
+    // Create a new config with updated data directory
+    let new_path = PathBuf::from(path);
+    let mut config_manager = state.config_manager.lock().unwrap();
+    config_manager.update_data_directory(new_path)
+        .map_err(|e| e.to_string())?;
+    
+    // Change working directory to new notes directory for relative path support
+    let notes_directory = config_manager.get_notes_directory();
+    if let Err(e) = std::env::set_current_dir(&notes_directory) {
+        log_warn!("Failed to change working directory to {}: {}", notes_directory.display(), e);
+    } else {
+        log_info!("Changed working directory to: {}", notes_directory.display());
+    }
+    
+    log_debug!("Data directory updated successfully");
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn reinitialize_data_directory(state: State<'_, Arc<AppState>>, path: String) -> Result<bool, String> {
+    log_info!("Reinitializing data directory: {}", path);
+    use std::path::PathBuf;
+    
+    // Create a new config with updated data directory
+    let new_path = PathBuf::from(path);
+    let mut config_manager = state.config_manager.lock().unwrap();
+    config_manager.update_data_directory(new_path.clone())
+        .map_err(|e| e.to_string())?;
+    
+    // Change working directory to new notes directory for relative path support
+    let notes_directory = config_manager.get_notes_directory();
+    if let Err(e) = std::env::set_current_dir(&notes_directory) {
+        log_warn!("Failed to change working directory to {}: {}", notes_directory.display(), e);
+    } else {
+        log_info!("Changed working directory to: {}", notes_directory.display());
+    }
+    
+    log_debug!("Data directory reinitialized successfully");
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn mark_setup_complete(state: State<'_, Arc<AppState>>) -> Result<bool, String> {
+    log_info!("Marking setup as complete");
+    match state.config_manager.lock().unwrap().mark_setup_complete() {
+        Ok(_) => {
+            log_debug!("Setup marked as complete");
+            
+            // Change working directory to notes directory for relative path support
+            let notes_directory = state.config_manager.lock().unwrap().get_notes_directory();
+            if let Err(e) = std::env::set_current_dir(&notes_directory) {
+                log_warn!("Failed to change working directory to {}: {}", notes_directory.display(), e);
+            } else {
+                log_info!("Changed working directory to: {}", notes_directory.display());
+            }
+            
+            Ok(true)
+        }
+        Err(e) => {
+            log_error!("Failed to mark setup as complete: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn sync_external_files(state: State<'_, Arc<AppState>>) -> Result<Vec<Note>, String> {
+    log_info!("Syncing external files");
+    let result = state.notes_manager.sync_external_files().await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(notes) = &result {
+        log_debug!("Synced {} notes from external files", notes.len());
+    }
+    
+    result
+}
+
+// Daily notes / journal commands
+fn daily_notes_config(state: &State<'_, Arc<AppState>>) -> crate::config::DailyNotesConfig {
+    let config_manager = state.config_manager.lock().unwrap();
+    config_manager.get_config().daily_notes.clone().unwrap_or_default()
+}
+
+fn parse_note_date(date: Option<&str>) -> Result<chrono::NaiveDate, String> {
+    match date {
+        Some(date) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date '{}': {}", date, e)),
+        None => Ok(chrono::Local::now().date_naive()),
+    }
+}
+
+#[tauri::command]
+pub async fn open_daily_note(state: State<'_, Arc<AppState>>, date: Option<String>) -> Result<DailyNote, String> {
+    log_info!("Opening daily note for date: {:?}", date);
+    let date = parse_note_date(date.as_deref())?;
+    let config = daily_notes_config(&state);
+    
+    let result = state.notes_manager.open_daily_note(date, &config).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(daily) => log_debug!("Daily note for {} is {}", daily.date, daily.note.id),
+        Err(e) => log_error!("Error opening daily note for {}: {}", date, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn get_adjacent_daily_note(state: State<'_, Arc<AppState>>, date: String, forward: bool) -> Result<Option<DailyNote>, String> {
+    log_info!("Getting {} daily note from {}", if forward { "next" } else { "previous" }, date);
+    let date = parse_note_date(Some(&date))?;
+    let config = daily_notes_config(&state);
+    
+    state.notes_manager.get_adjacent_daily_note(date, forward, &config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_journal(state: State<'_, Arc<AppState>>) -> Result<Vec<DailyNote>, String> {
+    log_info!("Getting journal");
+    let config = daily_notes_config(&state);
+    let result = state.notes_manager.get_journal(&config).await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(journal) = &result {
+        log_debug!("Found {} daily notes", journal.len());
+    }
+    
+    result
+}
+
+// Task commands
+#[tauri::command]
+pub async fn list_tasks(state: State<'_, Arc<AppState>>, filter: Option<TaskFilter>) -> Result<Vec<Task>, String> {
+    log_info!("Listing tasks with filter: {:?}", filter);
+    let filter = filter.unwrap_or_default();
+    let result = state.notes_manager.list_tasks(&filter).await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(tasks) = &result {
+        log_debug!("Found {} tasks", tasks.len());
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn toggle_task(state: State<'_, Arc<AppState>>, note_id: String, line: usize) -> Result<Option<Task>, CommandError> {
+    log_info!("Toggling task at line {} of note {}", line, note_id);
+    let result = state.notes_manager.toggle_task(&note_id, line).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(task)) => log_debug!("Task '{}' is now done={}", task.text, task.done),
+        Ok(None) => log_debug!("Note {} not found for task toggle", note_id),
+        Err(e) => log_error!("Error toggling task in note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+// Custom property commands
+#[tauri::command]
+pub async fn set_note_property(state: State<'_, Arc<AppState>>, note_id: String, key: String, value: serde_yaml::Value) -> Result<Option<Note>, CommandError> {
+    log_info!("Setting property '{}' on note {}", key, note_id);
+    let result = state.notes_manager.set_note_property(&note_id, &key, value).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully set property '{}' on note {}", key, note_id),
+        Ok(None) => log_debug!("Note {} not found for property update", note_id),
+        Err(e) => log_error!("Error setting property '{}' on note {}: {}", key, note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn unset_note_property(state: State<'_, Arc<AppState>>, note_id: String, key: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Removing property '{}' from note {}", key, note_id);
+    let result = state.notes_manager.unset_note_property(&note_id, &key).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully removed property '{}' from note {}", key, note_id),
+        Ok(None) => log_debug!("Note {} not found for property removal", note_id),
+        Err(e) => log_error!("Error removing property '{}' from note {}: {}", key, note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn get_vault_properties(state: State<'_, Arc<AppState>>) -> Result<Vec<PropertySummary>, String> {
+    log_info!("Getting vault properties");
+    let result = state.notes_manager.get_vault_properties().await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(properties) = &result {
+        log_debug!("Found {} property keys", properties.len());
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn query_notes_by_property(state: State<'_, Arc<AppState>>, query: PropertyQuery) -> Result<Vec<Note>, String> {
+    log_info!("Querying notes by property: {:?}", query);
+    let result = state.notes_manager.query_notes_by_property(&query).await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(notes) = &result {
+        log_debug!("Found {} notes matching property query", notes.len());
+    }
+    
+    result
+}
+
+// Board commands
+#[tauri::command]
+pub async fn get_board(state: State<'_, Arc<AppState>>, status_field: Option<String>, filter: Option<BoardFilter>) -> Result<Board, String> {
+    let status_field = status_field.unwrap_or_else(|| "status".to_string());
+    log_info!("Getting board for field: {}", status_field);
+    let result = state.notes_manager.get_board(&status_field, filter.as_ref()).await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(board) = &result {
+        log_debug!("Board '{}' has {} columns", status_field, board.columns.len());
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn move_card(state: State<'_, Arc<AppState>>, note_id: String, status_field: Option<String>, to_state: String, index: usize) -> Result<Option<Note>, CommandError> {
+    let status_field = status_field.unwrap_or_else(|| "status".to_string());
+    log_info!("Moving card {} to '{}' at {} on board '{}'", note_id, to_state, index, status_field);
+    let result = state.notes_manager.move_card(&note_id, &status_field, &to_state, index).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully moved card {}", note_id),
+        Ok(None) => log_debug!("Note {} not found for card move", note_id),
+        Err(e) => log_error!("Error moving card {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn get_board_states(state: State<'_, Arc<AppState>>, status_field: String) -> Result<Vec<String>, String> {
+    log_info!("Getting board states for field: {}", status_field);
+    let notes_directory = state.config_manager.lock().unwrap().get_notes_directory();
+    let settings = crate::vault::VaultSettings::load(&notes_directory)
+        .map_err(|e| e.to_string())?;
+    
+    Ok(settings.board(&status_field).states)
+}
+
+#[tauri::command]
+pub async fn set_board_states(state: State<'_, Arc<AppState>>, status_field: String, states: Vec<String>) -> Result<bool, String> {
+    log_info!("Setting board states for field {}: {:?}", status_field, states);
+    
+    let states: Vec<String> = states.into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if states.is_empty() {
+        return Err("A board needs at least one state".to_string());
+    }
+    
+    let notes_directory = state.config_manager.lock().unwrap().get_notes_directory();
+    let mut settings = crate::vault::VaultSettings::load(&notes_directory)
+        .map_err(|e| e.to_string())?;
+    settings.boards.insert(status_field, crate::vault::BoardConfig { states });
+    settings.save(&notes_directory)
+        .map_err(|e| e.to_string())?;
+    
+    Ok(true)
+}
+
+// Reminder commands
+#[tauri::command]
+pub async fn get_reminders(state: State<'_, Arc<AppState>>) -> Result<Vec<Reminder>, String> {
+    log_info!("Getting reminders");
+    let result = state.notes_manager.get_reminders().await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(reminders) = &result {
+        log_debug!("Found {} pending reminders", reminders.len());
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn set_reminder(state: State<'_, Arc<AppState>>, note_id: String, remind_at: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Setting reminder for note {} at {}", note_id, remind_at);
+    let result = state.notes_manager.set_reminder(&note_id, &remind_at).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully set reminder for note {}", note_id),
+        Ok(None) => log_debug!("Note {} not found for reminder", note_id),
+        Err(e) => log_error!("Error setting reminder for note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn snooze_reminder(state: State<'_, Arc<AppState>>, note_id: String, minutes: i64) -> Result<Option<Note>, CommandError> {
+    log_info!("Snoozing reminder for note {} by {} minutes", note_id, minutes);
+    let result = state.notes_manager.snooze_reminder(&note_id, minutes).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(note)) => log_debug!("Reminder for note {} snoozed until {:?}", note_id, note.remind),
+        Ok(None) => log_debug!("Note {} not found for snooze", note_id),
+        Err(e) => log_error!("Error snoozing reminder for note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn dismiss_reminder(state: State<'_, Arc<AppState>>, note_id: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Dismissing reminder for note {}", note_id);
+    let result = state.notes_manager.dismiss_reminder(&note_id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully dismissed reminder for note {}", note_id),
+        Ok(None) => log_debug!("Note {} not found for dismiss", note_id),
+        Err(e) => log_error!("Error dismissing reminder for note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+// Outline and section commands
+#[tauri::command]
+pub async fn get_note_outline(state: State<'_, Arc<AppState>>, note_id: String) -> Result<Option<Vec<OutlineHeading>>, String> {
+    log_info!("Getting outline for note: {}", note_id);
+    let result = state.notes_manager.get_note_outline(&note_id).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(Some(outline)) => log_debug!("Note {} has {} top-level headings", note_id, outline.len()),
+        Ok(None) => log_debug!("Note {} not found for outline", note_id),
+        Err(e) => log_error!("Error getting outline for note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn get_note_section(state: State<'_, Arc<AppState>>, note_id: String, heading: String) -> Result<Option<NoteSection>, String> {
+    log_info!("Getting section '{}' of note {}", heading, note_id);
+    state.notes_manager.get_note_section(&note_id, &heading).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn replace_note_section(state: State<'_, Arc<AppState>>, note_id: String, heading: String, content: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Replacing section '{}' of note {}", heading, note_id);
+    let result = state.notes_manager.replace_note_section(&note_id, &heading, &content).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully replaced section '{}' of note {}", heading, note_id),
+        Ok(None) => log_debug!("Note {} not found for section replace", note_id),
+        Err(e) => log_error!("Error replacing section '{}' of note {}: {}", heading, note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn move_note_section(state: State<'_, Arc<AppState>>, note_id: String, heading: String, target_note_id: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Moving section '{}' from note {} to note {}", heading, note_id, target_note_id);
+    let result = state.notes_manager.move_note_section(&note_id, &heading, &target_note_id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully moved section '{}' to note {}", heading, target_note_id),
+        Ok(None) => log_debug!("Source or target note not found for section move"),
+        Err(e) => log_error!("Error moving section '{}': {}", heading, e),
+    }
+    
+    result
+}
+
+// Statistics commands
+#[tauri::command]
+pub async fn get_vault_stats(state: State<'_, Arc<AppState>>) -> Result<VaultStats, String> {
+    log_info!("Getting vault statistics");
+    
+    // Git history only contributes when sync is enabled; the config lock is
+    // released before walking commits
+    let git_sync = {
+        let config_manager = state.config_manager.lock().unwrap();
+        match &config_manager.get_config().git_sync {
+            Some(git_config) if git_config.enabled => Some((config_manager.get_notes_directory(), git_config.clone())),
+            _ => None,
+        }
+    };
+    let git_activity = match git_sync {
+        Some((notes_directory, git_config)) => {
+            let activity = tokio::task::spawn_blocking(move || {
+                crate::sync::GitSyncManager::new(notes_directory, git_config).get_daily_activity(5000)
+            }).await;
+            match activity {
+                Ok(Ok(activity)) => Some(activity),
+                Ok(Err(e)) => {
+                    log_warn!("Failed to read git activity for statistics: {}", e);
+                    None
+                }
+                Err(e) => {
+                    log_warn!("Failed to read git activity for statistics: {}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+    
+    let result = state.notes_manager.get_vault_stats(git_activity.as_ref()).await
+        .map_err(|e| e.to_string());
+    
+    if let Ok(stats) = &result {
+        log_debug!("Vault has {} notes, {} words, streak {}", stats.note_count, stats.total_words, stats.current_streak);
+    }
+    
+    result
+}
+
+// Note restructuring commands
+#[tauri::command]
+pub async fn duplicate_note(state: State<'_, Arc<AppState>>, note_id: String) -> Result<Option<Note>, String> {
+    log_info!("Duplicating note {}", note_id);
+    
+    let result = state.notes_manager.duplicate_note(&note_id).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(Some(note)) => log_debug!("Duplicated note {} as {}", note_id, note.id),
+        Ok(None) => log_debug!("Note {} not found for duplication", note_id),
+        Err(e) => log_error!("Error duplicating note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn merge_notes(state: State<'_, Arc<AppState>>, note_ids: Vec<String>, target_id: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Merging {} notes into {}", note_ids.len(), target_id);
+    
+    let result = state.notes_manager.merge_notes(&note_ids, &target_id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully merged notes into {}", target_id),
+        Ok(None) => log_debug!("Target note {} not found for merge", target_id),
+        Err(e) => log_error!("Error merging notes into {}: {}", target_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn split_note(state: State<'_, Arc<AppState>>, note_id: String, heading_level: u8) -> Result<Vec<Note>, CommandError> {
+    log_info!("Splitting note {} at heading level {}", note_id, heading_level);
+    
+    let result = state.notes_manager.split_note(&note_id, heading_level).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(notes) => log_debug!("Split note {} into {} notes", note_id, notes.len()),
+        Err(e) => log_error!("Error splitting note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+// Pinned note commands
+#[tauri::command]
+pub async fn pin_note(state: State<'_, Arc<AppState>>, note_id: String, tag: Option<String>) -> Result<bool, String> {
+    log_info!("Pinning note {} (tag: {:?})", note_id, tag);
+    
+    let result = state.notes_manager.pin_note(&note_id, tag.as_deref()).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(true) => log_debug!("Successfully pinned note {}", note_id),
+        Ok(false) => log_debug!("Note {} not found for pinning", note_id),
+        Err(e) => log_error!("Error pinning note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn unpin_note(state: State<'_, Arc<AppState>>, note_id: String, tag: Option<String>) -> Result<bool, String> {
+    log_info!("Unpinning note {} (tag: {:?})", note_id, tag);
+    
+    let result = state.notes_manager.unpin_note(&note_id, tag.as_deref()).await
+        .map_err(|e| e.to_string());
+    
+    if let Err(e) = &result {
+        log_error!("Error unpinning note {}: {}", note_id, e);
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn set_manual_order(state: State<'_, Arc<AppState>>, note_ids: Vec<String>, tag: Option<String>) -> Result<Vec<String>, String> {
+    log_info!("Setting manual order of {} notes for {:?}", note_ids.len(), tag);
+    
+    let result = state.notes_manager.set_manual_order(&note_ids, tag.as_deref()).await
+        .map_err(|e| e.to_string());
+    
+    if let Err(e) = &result {
+        log_error!("Failed to set manual order: {}", e);
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn reorder_pinned_notes(state: State<'_, Arc<AppState>>, note_ids: Vec<String>, tag: Option<String>) -> Result<Vec<String>, String> {
+    log_info!("Reordering {} pinned notes (tag: {:?})", note_ids.len(), tag);
+    
+    let result = state.notes_manager.reorder_pinned_notes(&note_ids, tag.as_deref()).await
+        .map_err(|e| e.to_string());
+    
+    if let Err(e) = &result {
+        log_error!("Error reordering pinned notes: {}", e);
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn get_pinned_note_ids(state: State<'_, Arc<AppState>>, tag: Option<String>) -> Result<Vec<String>, String> {
+    log_info!("Getting pinned notes (tag: {:?})", tag);
+    state.notes_manager.get_pinned_note_ids(tag.as_deref()).await
+        .map_err(|e| e.to_string())
+}
+
+// Archive commands
+#[tauri::command]
+pub async fn get_archived_notes(state: State<'_, Arc<AppState>>, sort: Option<NoteSort>) -> Result<Vec<Note>, String> {
+    log_info!("Getting archived notes");
+    let result = state.notes_manager.get_archived_notes().await
+        .and_then(|notes| sorted_notes(&state, notes, sort, None))
+        .map_err(|e| e.to_string());
+    
+    if let Ok(notes) = &result {
+        log_debug!("Retrieved {} archived notes", notes.len());
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn archive_note(state: State<'_, Arc<AppState>>, id: String) -> Result<bool, CommandError> {
+    log_info!("Archiving note {}", id);
+    
+    let result = state.notes_manager.archive_note(&id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(true) => log_debug!("Successfully archived note {}", id),
+        Ok(false) => log_debug!("Note {} not found for archiving", id),
+        Err(e) => log_error!("Error archiving note {}: {}", id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn unarchive_note(state: State<'_, Arc<AppState>>, id: String) -> Result<bool, CommandError> {
+    log_info!("Unarchiving note {}", id);
+    
+    let result = state.notes_manager.unarchive_note(&id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(true) => log_debug!("Successfully unarchived note {}", id),
+        Ok(false) => log_debug!("Note {} not found for unarchiving", id),
+        Err(e) => log_error!("Error unarchiving note {}: {}", id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn archive_notes(state: State<'_, Arc<AppState>>, ids: Vec<String>) -> Result<BulkResult, CommandError> {
+    log_info!("Archiving {} notes", ids.len());
+    
+    let result = state.notes_manager.set_notes_archived(&ids, true).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(archived) => log_debug!("Archived {} of {} notes, {} locked", archived.updated.len(), ids.len(), archived.skipped_locked.len()),
+        Err(e) => log_error!("Error archiving notes: {}", e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn unarchive_notes(state: State<'_, Arc<AppState>>, ids: Vec<String>) -> Result<BulkResult, CommandError> {
+    log_info!("Unarchiving {} notes", ids.len());
+    
+    let result = state.notes_manager.set_notes_archived(&ids, false).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(unarchived) => log_debug!("Unarchived {} of {} notes, {} locked", unarchived.updated.len(), ids.len(), unarchived.skipped_locked.len()),
+        Err(e) => log_error!("Error unarchiving notes: {}", e),
+    }
+    
+    result
+}
+
+// Lock commands
+#[tauri::command]
+pub async fn lock_note(state: State<'_, Arc<AppState>>, note_id: String) -> Result<Option<Note>, String> {
+    log_info!("Locking note {}", note_id);
+    
+    let result = state.notes_manager.lock_note(&note_id).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully locked note {}", note_id),
+        Ok(None) => log_debug!("Note {} not found for locking", note_id),
+        Err(e) => log_error!("Error locking note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn unlock_note(state: State<'_, Arc<AppState>>, note_id: String) -> Result<Option<Note>, String> {
+    log_info!("Unlocking note {}", note_id);
+    
+    let result = state.notes_manager.unlock_note(&note_id).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully unlocked note {}", note_id),
+        Ok(None) => log_debug!("Note {} not found for unlocking", note_id),
+        Err(e) => log_error!("Error unlocking note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+// Encryption commands
+#[tauri::command]
+pub async fn encrypt_note(state: State<'_, Arc<AppState>>, note_id: String, passphrase: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Encrypting note {}", note_id);
+    
+    let result = state.notes_manager.encrypt_note(&note_id, &passphrase).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully encrypted note {}", note_id),
+        Ok(None) => log_debug!("Note {} not found for encryption", note_id),
+        Err(e) => log_error!("Error encrypting note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn decrypt_note(state: State<'_, Arc<AppState>>, note_id: String, passphrase: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Removing encryption from note {}", note_id);
+    
+    let result = state.notes_manager.decrypt_note(&note_id, &passphrase).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully decrypted note {}", note_id),
+        Ok(None) => log_debug!("Note {} not found for decryption", note_id),
+        Err(e) => log_error!("Error decrypting note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn unlock_encrypted_note(state: State<'_, Arc<AppState>>, note_id: String, passphrase: String) -> Result<Option<Note>, String> {
+    log_info!("Unlocking encrypted note {}", note_id);
+    
+    let result = state.notes_manager.unlock_encrypted_note(&note_id, &passphrase).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully unlocked encrypted note {}", note_id),
+        Ok(None) => log_debug!("Note {} not found for unlocking", note_id),
+        Err(e) => log_warn!("Failed to unlock encrypted note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn lock_encrypted_note(state: State<'_, Arc<AppState>>, note_id: String) -> Result<bool, String> {
+    log_info!("Locking encrypted note {}", note_id);
+    state.notes_manager.lock_encrypted_note(&note_id).await
+        .map_err(|e| e.to_string())
+}
+
+// Version history commands
+#[tauri::command]
+pub async fn list_note_versions(state: State<'_, Arc<AppState>>, note_id: String) -> Result<Vec<NoteVersion>, String> {
+    log_info!("Listing versions of note {}", note_id);
+    
+    let result = state.notes_manager.list_note_versions(&note_id).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(versions) => log_debug!("Found {} versions of note {}", versions.len(), note_id),
+        Err(e) => log_error!("Error listing versions of note {}: {}", note_id, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn get_note_version(state: State<'_, Arc<AppState>>, note_id: String, version_id: String) -> Result<Option<Note>, String> {
+    log_info!("Getting version {} of note {}", version_id, note_id);
+    state.notes_manager.get_note_version(&note_id, &version_id).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn diff_note_version(state: State<'_, Arc<AppState>>, note_id: String, version_id: String) -> Result<Option<NoteVersionDiff>, String> {
+    log_info!("Diffing version {} of note {} against current", version_id, note_id);
+    
+    let result = state.notes_manager.diff_note_version(&note_id, &version_id).await
         .map_err(|e| e.to_string());
     
-    if let Ok(notes) = &result {
-        log_debug!("Found {} trash notes", notes.len());
+    if let Err(e) = &result {
+        log_error!("Error diffing version {} of note {}: {}", version_id, note_id, e);
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn get_notes_by_tag(state: State<'_, Arc<AppState>>, tag_name: String) -> Result<Vec<Note>, String> {
-    log_info!("Getting notes by tag: {}", tag_name);
-    let result = state.notes_manager.get_notes_by_tag(&tag_name).await
-        .map_err(|e| e.to_string());
+pub async fn restore_note_version(state: State<'_, Arc<AppState>>, note_id: String, version_id: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Restoring version {} of note {}", version_id, note_id);
     
-    if let Ok(notes) = &result {
-        log_debug!("Found {} notes with tag '{}'", notes.len(), tag_name);
+    let result = state.notes_manager.restore_note_version(&note_id, &version_id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully restored version {} of note {}", version_id, note_id),
+        Ok(None) => log_debug!("Version {} of note {} not found", version_id, note_id),
+        Err(e) => log_error!("Error restoring version {} of note {}: {}", version_id, note_id, e),
     }
     
     result
 }
 
+// Draft journal commands
 #[tauri::command]
-pub async fn get_all_tags(state: State<'_, Arc<AppState>>) -> Result<Vec<Tag>, String> {
-    log_info!("Getting all tags");
-    let result = state.tags_manager.get_all_tags().await
+pub async fn save_draft(state: State<'_, Arc<AppState>>, note_id: String, content: String) -> Result<bool, String> {
+    // Called on every editor flush, so only failures are logged
+    let result = state.notes_manager.save_draft(&note_id, &content).await
         .map_err(|e| e.to_string());
     
-    if let Ok(tags) = &result {
-        log_debug!("Found {} tags", tags.len());
+    if let Err(e) = &result {
+        log_error!("Error saving draft for note {}: {}", note_id, e);
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn get_note(state: State<'_, Arc<AppState>>, id: String) -> Result<Option<Note>, String> {
-    log_info!("Getting note with id: {}", id);
-    let result = state.notes_manager.get_note(&id).await
+pub async fn get_recoverable_drafts(state: State<'_, Arc<AppState>>) -> Result<Vec<DraftRecovery>, String> {
+    log_info!("Checking draft journal for unsaved changes");
+    
+    let result = state.notes_manager.get_recoverable_drafts().await
         .map_err(|e| e.to_string());
     
     match &result {
-        Ok(Some(note)) => log_debug!("Found note with title: {}", note.title),
-        Ok(None) => log_debug!("Note with id '{}' not found", id),
-        Err(e) => log_error!("Error getting note {}: {}", id, e),
+        Ok(drafts) if !drafts.is_empty() => log_warn!("Found {} recoverable drafts", drafts.len()),
+        Ok(_) => log_debug!("No recoverable drafts"),
+        Err(e) => log_error!("Error reading draft journal: {}", e),
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn create_note(state: State<'_, Arc<AppState>>, request: CreateNoteRequest) -> Result<Note, String> {
-    log_info!("Creating new note with title: {}", request.title);
-    let result = state.notes_manager.create_note(request).await
-        .map_err(|e| e.to_string());
+pub async fn recover_draft(state: State<'_, Arc<AppState>>, note_id: String) -> Result<Option<Note>, CommandError> {
+    log_info!("Recovering draft for note {}", note_id);
     
-    if let Ok(note) = &result {
-        log_debug!("Successfully created note with id: {}", note.id);
+    let result = state.notes_manager.recover_draft(&note_id).await
+        .map_err(CommandError::from);
+    
+    match &result {
+        Ok(Some(_)) => log_debug!("Successfully recovered draft for note {}", note_id),
+        Ok(None) => log_debug!("No draft found for note {}", note_id),
+        Err(e) => log_error!("Error recovering draft for note {}: {}", note_id, e),
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn update_note(state: State<'_, Arc<AppState>>, request: UpdateNoteRequest) -> Result<Option<Note>, String> {
-    log_info!("Updating note with id: {}", request.id);
-    let result = state.notes_manager.update_note(request).await
+pub async fn discard_draft(state: State<'_, Arc<AppState>>, note_id: String) -> Result<(), String> {
+    log_info!("Discarding draft for note {}", note_id);
+    state.notes_manager.discard_draft(&note_id).await
+        .map_err(|e| e.to_string())
+}
+
+// Activity commands
+#[tauri::command]
+pub async fn get_activity_feed(state: State<'_, Arc<AppState>>, limit: Option<usize>) -> Result<Vec<ActivityEntry>, String> {
+    log_info!("Loading activity feed");
+    state.notes_manager.get_activity_feed(limit.unwrap_or(50)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn undo_last_operation(state: State<'_, Arc<AppState>>) -> Result<Option<UndoResult>, String> {
+    log_info!("Undoing last operation");
+    
+    let result = state.notes_manager.undo_last_operation().await
         .map_err(|e| e.to_string());
     
     match &result {
-        Ok(Some(note)) => log_debug!("Successfully updated note with title: {}", note.title),
-        Ok(None) => log_debug!("Note with id not found for update"),
-        Err(e) => log_error!("Error updating note: {}", e),
+        Ok(Some(undo)) => log_debug!("Undid '{}': {} restored, {} skipped", undo.operation.summary, undo.restored.len(), undo.skipped.len()),
+        Ok(None) => log_debug!("Nothing to undo"),
+        Err(e) => log_error!("Error undoing last operation: {}", e),
     }
     
     result
 }
 
+// Tag rule commands
 #[tauri::command]
-pub async fn delete_note(state: State<'_, Arc<AppState>>, id: String) -> Result<bool, String> {
-    log_info!("Deleting note with id: {}", id);
-    let result = state.notes_manager.delete_note(&id).await
+pub async fn list_tag_rules(state: State<'_, Arc<AppState>>) -> Result<Vec<crate::vault::TagRule>, String> {
+    state.tags_manager.list_tag_rules().await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_tag_rule(state: State<'_, Arc<AppState>>, rule: crate::vault::TagRule) -> Result<crate::vault::TagRule, String> {
+    log_info!("Saving tag rule: {}", rule.name);
+    let result = state.tags_manager.save_tag_rule(rule).await
         .map_err(|e| e.to_string());
     
     match &result {
-        Ok(true) => {
-            log_debug!("Successfully deleted note {}", id);
-            // Cleanup unused tags after note deletion
-            if let Err(e) = state.tags_manager.cleanup_unused_tags().await {
-                log_error!("Failed to cleanup unused tags after deleting note {}: {}", id, e);
-            }
-        },
-        Ok(false) => log_debug!("Note {} not found for deletion", id),
-        Err(e) => log_error!("Error deleting note {}: {}", id, e),
+        Ok(rule) => log_debug!("Saved tag rule {}", rule.id),
+        Err(e) => log_error!("Error saving tag rule: {}", e),
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn search_notes(state: State<'_, Arc<AppState>>, request: SearchRequest) -> Result<Vec<Note>, String> {
-    log_info!("Searching notes with query: '{}', tag_filter: {:?}", request.query, request.tag_filter);
-    
-    let result = state.notes_manager.search_notes(&request.query, request.tag_filter.as_deref()).await
-        .map_err(|e| e.to_string());
-    
-    if let Ok(notes) = &result {
-        log_debug!("Found {} notes matching search criteria", notes.len());
-    }
-    
-    result
+pub async fn delete_tag_rule(state: State<'_, Arc<AppState>>, rule_id: String) -> Result<bool, String> {
+    log_info!("Deleting tag rule: {}", rule_id);
+    state.tags_manager.delete_tag_rule(&rule_id).await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn restore_note(state: State<'_, Arc<AppState>>, id: String) -> Result<bool, String> {
-    log_info!("Restoring note with id: {}", id);
-    let result = state.notes_manager.restore_note(&id).await
+pub async fn preview_tag_rule(state: State<'_, Arc<AppState>>, rule: crate::vault::TagRule) -> Result<Vec<TagRulePreview>, String> {
+    log_info!("Previewing tag rule: {}", rule.name);
+    let result = state.tags_manager.preview_tag_rule(rule).await
         .map_err(|e| e.to_string());
     
     match &result {
-        Ok(true) => log_debug!("Successfully restored note {}", id),
-        Ok(false) => log_debug!("Note {} not found for restoration", id),
-        Err(e) => log_error!("Error restoring note {}: {}", id, e),
+        Ok(notes) => log_debug!("Tag rule would change {} notes", notes.len()),
+        Err(e) => log_error!("Error previewing tag rule: {}", e),
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn permanently_delete_note(state: State<'_, Arc<AppState>>, id: String) -> Result<bool, String> {
-    log_info!("Permanently deleting note with id: {}", id);
-    let result = state.notes_manager.permanently_delete_note(&id).await
+pub async fn apply_rules_to_vault(state: State<'_, Arc<AppState>>) -> Result<BulkResult, String> {
+    log_info!("Applying tag rules to the vault");
+    let result = state.tags_manager.apply_rules_to_vault().await
         .map_err(|e| e.to_string());
     
     match &result {
-        Ok(true) => {
-            log_debug!("Successfully permanently deleted note {}", id);
-            // Cleanup unused tags after permanent note deletion
-            if let Err(e) = state.tags_manager.cleanup_unused_tags().await {
-                log_error!("Failed to cleanup unused tags after permanently deleting note {}: {}", id, e);
-            }
-        },
-        Ok(false) => log_debug!("Note {} not found for permanent deletion", id),
-        Err(e) => log_error!("Error permanently deleting note {}: {}", id, e),
+        Ok(r) if !r.skipped_locked.is_empty() => log_warn!("Tag rules changed {} notes, skipped {} locked notes", r.updated.len(), r.skipped_locked.len()),
+        Ok(r) => log_debug!("Tag rules changed {} notes", r.updated.len()),
+        Err(e) => log_error!("Error applying tag rules: {}", e),
     }
     
     result
 }
 
+// Tag management commands
 #[tauri::command]
-pub async fn show_directory_dialog() -> Result<Option<String>, String> {
-    log_info!("Showing directory dialog");
-    use tauri::api::dialog::FileDialogBuilder;
-    
-    let (tx, rx) = std::sync::mpsc::channel();
-    
-    FileDialogBuilder::new()
-        .set_title("Select Data Directory")
-        .pick_folder(move |path_buf| {
-            let _ = tx.send(path_buf.map(|p| p.to_string_lossy().to_string()));
-        });
-    
-    let result = rx.recv().map_err(|e| e.to_string());
+pub async fn create_tag(state: State<'_, Arc<AppState>>, name: String) -> Result<Tag, String> {
+    log_info!("Creating new tag: {}", name);
+    let result = state.tags_manager.create_tag(&name).await
+        .map_err(|e| e.to_string());
     
-    if let Ok(Some(path)) = &result {
-        log_debug!("Selected directory: {}", path);
+    if let Ok(tag) = &result {
+        log_debug!("Successfully created tag with id: {}", tag.id);
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn update_data_directory(state: State<'_, Arc<AppState>>, path: String) -> Result<bool, String> {
-    log_info!("Updating data directory to: {}", path);
-    use std::path::PathBuf;
-    
-    // Create a new config with updated data directory
-    let new_path = PathBuf::from(path);
-    let mut config_manager = state.config_manager.lock().unwrap();
-    config_manager.update_data_directory(new_path)
-        .map_err(|e| e.to_string())?;
+pub async fn delete_tag(state: State<'_, Arc<AppState>>, tag_id: String) -> Result<BulkResult, String> {
+    log_info!("Deleting tag with id: {}", tag_id);
+    let result = state.tags_manager.delete_tag(&tag_id).await
+        .map_err(|e| e.to_string());
     
-    // Change working directory to new notes directory for relative path support
-    let notes_directory = config_manager.get_notes_directory();
-    if let Err(e) = std::env::set_current_dir(&notes_directory) {
-        log_warn!("Failed to change working directory to {}: {}", notes_directory.display(), e);
-    } else {
-        log_info!("Changed working directory to: {}", notes_directory.display());
+    match &result {
+        Ok(r) if !r.skipped_locked.is_empty() => log_warn!("Deleted tag {} from {} notes, skipped {} locked notes", tag_id, r.updated.len(), r.skipped_locked.len()),
+        Ok(r) if r.updated.is_empty() => log_debug!("Tag {} not found for deletion", tag_id),
+        Ok(_) => log_debug!("Successfully deleted tag {}", tag_id),
+        Err(e) => log_error!("Error deleting tag {}: {}", tag_id, e),
     }
     
-    log_debug!("Data directory updated successfully");
-    Ok(true)
+    result
 }
 
 #[tauri::command]
-pub async fn reinitialize_data_directory(state: State<'_, Arc<AppState>>, path: String) -> Result<bool, String> {
-    log_info!("Reinitializing data directory: {}", path);
-    use std::path::PathBuf;
-    
-    // Create a new config with updated data directory
-    let new_path = PathBuf::from(path);
-    let mut config_manager = state.config_manager.lock().unwrap();
-    config_manager.update_data_directory(new_path.clone())
-        .map_err(|e| e.to_string())?;
+pub async fn rename_tag(state: State<'_, Arc<AppState>>, tag_id: String, new_name: String) -> Result<BulkResult, String> {
+    log_info!("Renaming tag {} to: {}", tag_id, new_name);
+    let result = state.tags_manager.rename_tag(&tag_id, &new_name).await
+        .map_err(|e| e.to_string());
     
-    // Change working directory to new notes directory for relative path support
-    let notes_directory = config_manager.get_notes_directory();
-    if let Err(e) = std::env::set_current_dir(&notes_directory) {
-        log_warn!("Failed to change working directory to {}: {}", notes_directory.display(), e);
-    } else {
-        log_info!("Changed working directory to: {}", notes_directory.display());
+    match &result {
+        Ok(r) if !r.skipped_locked.is_empty() => log_warn!("Renamed tag {} on {} notes, skipped {} locked notes", tag_id, r.updated.len(), r.skipped_locked.len()),
+        Ok(r) if r.updated.is_empty() => log_debug!("Tag {} not found for renaming", tag_id),
+        Ok(_) => log_debug!("Successfully renamed tag to: {}", new_name),
+        Err(e) => log_error!("Error renaming tag {}: {}", tag_id, e),
     }
     
-    log_debug!("Data directory reinitialized successfully");
-    Ok(true)
+    result
 }
 
 #[tauri::command]
-pub async fn mark_setup_complete(state: State<'_, Arc<AppState>>) -> Result<bool, String> {
-    log_info!("Marking setup as complete");
-    match state.config_manager.lock().unwrap().mark_setup_complete() {
-        Ok(_) => {
-            log_debug!("Setup marked as complete");
-            
-            // Change working directory to notes directory for relative path support
-            let notes_directory = state.config_manager.lock().unwrap().get_notes_directory();
-            if let Err(e) = std::env::set_current_dir(&notes_directory) {
-                log_warn!("Failed to change working directory to {}: {}", notes_directory.display(), e);
-            } else {
-                log_info!("Changed working directory to: {}", notes_directory.display());
-            }
-            
-            Ok(true)
-        }
-        Err(e) => {
-            log_error!("Failed to mark setup as complete: {}", e);
-            Err(e.to_string())
-        }
+pub async fn update_tag(state: State<'_, Arc<AppState>>, request: UpdateTagRequest) -> Result<Tag, String> {
+    log_info!("Updating tag: {}", request.id);
+    let result = state.tags_manager.update_tag(request).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(tag) => log_debug!("Successfully updated tag {}", tag.name),
+        Err(e) => log_error!("Error updating tag: {}", e),
     }
+    
+    result
 }
 
 #[tauri::command]
-pub async fn sync_external_files(state: State<'_, Arc<AppState>>) -> Result<Vec<Note>, String> {
-    log_info!("Syncing external files");
-    let result = state.notes_manager.sync_external_files().await
+pub async fn merge_tags(state: State<'_, Arc<AppState>>, sources: Vec<String>, target: String) -> Result<BulkResult, String> {
+    log_info!("Merging {} tags into: {}", sources.len(), target);
+    let result = state.tags_manager.merge_tags(&sources, &target).await
         .map_err(|e| e.to_string());
     
-    if let Ok(notes) = &result {
-        log_debug!("Synced {} notes from external files", notes.len());
+    match &result {
+        Ok(r) if !r.skipped_locked.is_empty() => log_warn!("Merged tags on {} notes, skipped {} locked notes", r.updated.len(), r.skipped_locked.len()),
+        Ok(r) => log_debug!("Merged tags into {} on {} notes", target, r.updated.len()),
+        Err(e) => log_error!("Error merging tags into {}: {}", target, e),
     }
     
     result
 }
 
-// Tag management commands
 #[tauri::command]
-pub async fn create_tag(state: State<'_, Arc<AppState>>, name: String) -> Result<Tag, String> {
-    log_info!("Creating new tag: {}", name);
-    let result = state.tags_manager.create_tag(&name).await
+pub async fn normalize_tags(state: State<'_, Arc<AppState>>, dry_run: bool) -> Result<TagNormalization, String> {
+    log_info!("Normalizing tags (dry run: {})", dry_run);
+    let result = state.tags_manager.normalize_tags(dry_run).await
         .map_err(|e| e.to_string());
     
-    if let Ok(tag) = &result {
-        log_debug!("Successfully created tag with id: {}", tag.id);
+    match &result {
+        Ok(r) => log_debug!("{} tag names to normalize across {} notes", r.renames.len(), r.updated.len()),
+        Err(e) => log_error!("Error normalizing tags: {}", e),
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn delete_tag(state: State<'_, Arc<AppState>>, tag_id: String) -> Result<bool, String> {
-    log_info!("Deleting tag with id: {}", tag_id);
-    let result = state.tags_manager.delete_tag(&tag_id).await
+pub async fn sync_inline_tags(state: State<'_, Arc<AppState>>) -> Result<BulkResult, String> {
+    log_info!("Copying inline tags into front matter");
+    let result = state.tags_manager.sync_inline_tags().await
         .map_err(|e| e.to_string());
     
     match &result {
-        Ok(true) => log_debug!("Successfully deleted tag {}", tag_id),
-        Ok(false) => log_debug!("Tag {} not found for deletion", tag_id),
-        Err(e) => log_error!("Error deleting tag {}: {}", tag_id, e),
+        Ok(r) if !r.skipped_locked.is_empty() => log_warn!("Synced inline tags on {} notes, skipped {} locked notes", r.updated.len(), r.skipped_locked.len()),
+        Ok(r) => log_debug!("Synced inline tags on {} notes", r.updated.len()),
+        Err(e) => log_error!("Error syncing inline tags: {}", e),
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn rename_tag(state: State<'_, Arc<AppState>>, tag_id: String, new_name: String) -> Result<Option<Tag>, String> {
-    log_info!("Renaming tag {} to: {}", tag_id, new_name);
-    let result = state.tags_manager.rename_tag(&tag_id, &new_name).await
+pub async fn get_tag_policy(state: State<'_, Arc<AppState>>) -> Result<crate::vault::TagPolicy, String> {
+    state.tags_manager.get_tag_policy().await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_tag_policy(state: State<'_, Arc<AppState>>, policy: crate::vault::TagPolicy) -> Result<(), String> {
+    log_info!("Updating tag policy: {:?}", policy);
+    state.tags_manager.set_tag_policy(policy).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn suggest_tags(state: State<'_, Arc<AppState>>, note_id: String, limit: Option<usize>) -> Result<Vec<TagSuggestion>, String> {
+    log_info!("Suggesting tags for note: {}", note_id);
+    let result = state.tags_manager.suggest_tags(&note_id, limit.unwrap_or(10)).await
         .map_err(|e| e.to_string());
     
     match &result {
-        Ok(Some(tag)) => log_debug!("Successfully renamed tag to: {}", tag.name),
-        Ok(None) => log_debug!("Tag {} not found for renaming", tag_id),
-        Err(e) => log_error!("Error renaming tag {}: {}", tag_id, e),
+        Ok(suggestions) => log_debug!("Found {} tag suggestions for note {}", suggestions.len(), note_id),
+        Err(e) => log_error!("Error suggesting tags for note {}: {}", note_id, e),
     }
     
     result
 }
 
 #[tauri::command]
-pub async fn add_tag_to_note(state: State<'_, Arc<AppState>>, note_id: String, tag_name: String) -> Result<Tag, String> {
+pub async fn add_tag_to_note(state: State<'_, Arc<AppState>>, note_id: String, tag_name: String) -> Result<Tag, CommandError> {
     log_info!("Adding tag '{}' to note: {}", tag_name, note_id);
     let result = state.tags_manager.add_tag_to_note(&note_id, &tag_name).await
-        .map_err(|e| e.to_string());
+        .map_err(CommandError::from);
     
     if let Ok(tag) = &result {
         log_debug!("Successfully added tag '{}' to note {}", tag.name, note_id);
@@ -377,10 +1320,10 @@ pub async fn add_tag_to_note(state: State<'_, Arc<AppState>>, note_id: String, t
 }
 
 #[tauri::command]
-pub async fn remove_tag_from_note(state: State<'_, Arc<AppState>>, note_id: String, tag_name: String) -> Result<bool, String> {
+pub async fn remove_tag_from_note(state: State<'_, Arc<AppState>>, note_id: String, tag_name: String) -> Result<bool, CommandError> {
     log_info!("Removing tag '{}' from note: {}", tag_name, note_id);
     let result = state.tags_manager.remove_tag_from_note(&note_id, &tag_name).await
-        .map_err(|e| e.to_string());
+        .map_err(CommandError::from);
     
     match &result {
         Ok(true) => log_debug!("Successfully removed tag '{}' from note {}", tag_name, note_id),
@@ -418,13 +1361,15 @@ pub async fn search_tags(state: State<'_, Arc<AppState>>, query: String) -> Resu
 }
 
 #[tauri::command]
-pub async fn cleanup_unused_tags(state: State<'_, Arc<AppState>>) -> Result<usize, String> {
-    log_info!("Cleaning up unused tags");
-    let result = state.tags_manager.cleanup_unused_tags().await
+pub async fn cleanup_unused_tags(state: State<'_, Arc<AppState>>, dry_run: Option<bool>) -> Result<TagCleanup, String> {
+    // Leaving the flag out only previews
+    let dry_run = dry_run.unwrap_or(true);
+    log_info!("Cleaning up unused tags (dry run: {})", dry_run);
+    let result = state.tags_manager.cleanup_unused_tags(dry_run).await
         .map_err(|e| e.to_string());
     
     match &result {
-        Ok(count) => log_debug!("Successfully cleaned up {} unused tags", count),
+        Ok(cleanup) => log_debug!("Found {} unused tags (applied: {})", cleanup.tags.len(), cleanup.applied),
         Err(e) => log_error!("Error cleaning up unused tags: {}", e),
     }
     
@@ -435,54 +1380,30 @@ pub async fn cleanup_unused_tags(state: State<'_, Arc<AppState>>) -> Result<usiz
 pub async fn save_image_to_attachments(state: State<'_, Arc<AppState>>, filename: String, data: Vec<u8>) -> Result<String, String> {
     log_info!("Saving image to attachments: {}", filename);
     
-    use std::fs;
-    
-    // Get the notes directory from config
-    let config_manager = state.config_manager.lock().unwrap();
-    let notes_directory = config_manager.get_notes_directory();
-    let attachments_dir = notes_directory.join("attachments");
-    
-    // Create attachments directory if it doesn't exist
-    fs::create_dir_all(&attachments_dir)
-        .map_err(|e| format!("Failed to create attachments directory: {}", e))?;
-    
-    // Write the file
-    let file_path = attachments_dir.join(&filename);
-    fs::write(&file_path, &data)
-        .map_err(|e| format!("Failed to write image file: {}", e))?;
+    let result = state.attachments_manager.save_attachment(&filename, &data).await
+        .map_err(|e| format!("Failed to save image: {}", e));
     
-    // Return relative path for markdown
-    let relative_path = format!("attachments/{}", filename);
+    match &result {
+        Ok(path) => log_debug!("Successfully saved image to: {}", path),
+        Err(e) => log_error!("Error saving image {}: {}", filename, e),
+    }
     
-    log_debug!("Successfully saved image to: {}", file_path.display());
-    Ok(relative_path)
+    result
 }
 
 #[tauri::command]
 pub async fn save_image_for_text(state: State<'_, Arc<AppState>>, filename: String, data: Vec<u8>) -> Result<String, String> {
     log_info!("Saving image for text content: {}", filename);
     
-    use std::fs;
-    
-    // Get the notes directory from config
-    let config_manager = state.config_manager.lock().unwrap();
-    let notes_directory = config_manager.get_notes_directory();
-    let attachments_dir = notes_directory.join("attachments");
-    
-    // Create attachments directory if it doesn't exist
-    fs::create_dir_all(&attachments_dir)
-        .map_err(|e| format!("Failed to create attachments directory: {}", e))?;
-    
-    // Write the file
-    let file_path = attachments_dir.join(&filename);
-    fs::write(&file_path, &data)
-        .map_err(|e| format!("Failed to write image file: {}", e))?;
+    let result = state.attachments_manager.save_attachment(&filename, &data).await
+        .map_err(|e| format!("Failed to save image: {}", e));
     
-    // Return relative path for markdown
-    let relative_path = format!("attachments/{}", filename);
+    match &result {
+        Ok(path) => log_debug!("Successfully saved text image to: {}", path),
+        Err(e) => log_error!("Error saving image {}: {}", filename, e),
+    }
     
-    log_debug!("Successfully saved text image to: {}", file_path.display());
-    Ok(relative_path)
+    result
 }
 
 #[tauri::command]
@@ -511,49 +1432,12 @@ pub async fn add_attachment(state: State<'_, Arc<AppState>>, note_id: String, fi
         .ok_or("Invalid file path")?
         .to_string_lossy();
     
-    // 获取notes目录
-    let notes_directory = {
-        let config_manager = state.config_manager.lock().unwrap();
-        config_manager.get_notes_directory()
-    };
-    let attachments_dir = notes_directory.join("attachments");
-    
-    // 创建attachments目录（如果不存在）
-    fs::create_dir_all(&attachments_dir)
-        .map_err(|e| format!("Failed to create attachments directory: {}", e))?;
-    
-    // 生成目标文件名（避免重名）
-    let mut target_filename = file_name.to_string();
-    let mut counter = 1;
-    let target_path = loop {
-        let test_path = attachments_dir.join(&target_filename);
-        if !test_path.exists() {
-            break test_path;
-        }
-        
-        // 处理文件重名
-        if let Some(stem) = source_path.file_stem() {
-            if let Some(ext) = source_path.extension() {
-                target_filename = format!("{}_{}.{}", 
-                    stem.to_string_lossy(), 
-                    counter, 
-                    ext.to_string_lossy()
-                );
-            } else {
-                target_filename = format!("{}_{}", stem.to_string_lossy(), counter);
-            }
-        } else {
-            target_filename = format!("{}_{}", file_name, counter);
-        }
-        counter += 1;
-    };
-    
-    // 复制文件
-    fs::copy(&source_path, &target_path)
+    // 读取文件内容，相同内容的附件只保存一份
+    let data = fs::read(source_path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let relative_path = state.attachments_manager.save_attachment(&file_name, &data).await
         .map_err(|e| format!("Failed to copy file: {}", e))?;
     
-    let relative_path = format!("attachments/{}", target_filename);
-    
     // 将附件关联到笔记
     state.notes_manager.add_attachment_to_note(&note_id, &relative_path).await
         .map_err(|e| format!("Failed to link attachment to note: {}", e))?;
@@ -578,11 +1462,41 @@ pub async fn list_attachments(state: State<'_, Arc<AppState>>, note_id: String)
 }
 
 #[tauri::command]
-pub async fn delete_attachment(state: State<'_, Arc<AppState>>, note_id: String, relative_path: String) -> Result<bool, String> {
+pub async fn list_vault_attachments(state: State<'_, Arc<AppState>>, filter: Option<AttachmentFilter>) -> Result<Vec<Attachment>, String> {
+    log_info!("Listing vault attachments with filter: {:?}", filter);
+    let filter = filter.unwrap_or_default();
+    let result = state.attachments_manager.list_attachments(&filter).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(attachments) => log_debug!("Found {} attachments", attachments.len()),
+        Err(e) => log_error!("Error listing vault attachments: {}", e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn get_attachment_info(state: State<'_, Arc<AppState>>, path: String) -> Result<Option<AttachmentInfo>, String> {
+    log_info!("Getting attachment info: {}", path);
+    let result = state.attachments_manager.get_attachment_info(&path).await
+        .map_err(|e| e.to_string());
+    
+    match &result {
+        Ok(Some(info)) => log_debug!("Attachment {} is referenced by {} notes", path, info.referenced_by.len()),
+        Ok(None) => log_debug!("Attachment {} not found", path),
+        Err(e) => log_error!("Error getting attachment info {}: {}", path, e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn delete_attachment(state: State<'_, Arc<AppState>>, note_id: String, relative_path: String) -> Result<bool, CommandError> {
     log_info!("Deleting attachment {} from note {}", relative_path, note_id);
     
     state.notes_manager.remove_attachment_from_note(&note_id, &relative_path).await
-        .map_err(|e| format!("Failed to remove attachment from note: {}", e))?;
+        .map_err(CommandError::from)?;
         
     // Note: We don't delete the file immediately, cleanup_unreferenced_attachments will handle it.
     
@@ -645,7 +1559,7 @@ pub async fn export_note(state: State<'_, Arc<AppState>>, note_id: String, expor
     let _source_attachments_dir = notes_directory.join("attachments");
     
     // Extract attachment references from markdown content
-    let attachment_refs = extract_attachment_references(&note.content);
+    let attachment_refs = crate::attachments::extract_attachment_references(&note.content);
     let mut exported_count = 0;
     
     // Copy referenced attachments
@@ -729,45 +1643,21 @@ fn sanitize_filename(title: &str) -> String {
     sanitized.trim().to_string()
 }
 
-// Helper function to extract attachment references from markdown content
-fn extract_attachment_references(content: &str) -> Vec<String> {
-    let mut attachments = Vec::new();
-    
-    // Regex patterns for different markdown image/link formats
-    let patterns = [
-        // ![alt](attachments/filename)
-        r"!\[.*?\]\((attachments/[^)]+)\)",
-        // [text](attachments/filename)
-        r"\[.*?\]\((attachments/[^)]+)\)",
-        // ![alt](images/filename) - for images saved via save_image_for_text
-        r"!\[.*?\]\((images/[^)]+)\)",
-        // [text](images/filename)
-        r"\[.*?\]\((images/[^)]+)\)",
-    ];
-    
-    for pattern in &patterns {
-        if let Ok(re) = regex::Regex::new(pattern) {
-            for cap in re.captures_iter(content) {
-                if let Some(path) = cap.get(1) {
-                    attachments.push(path.as_str().to_string());
-                }
-            }
-        }
-    }
+#[tauri::command]
+pub async fn cleanup_unreferenced_attachments(state: State<'_, Arc<AppState>>, dry_run: Option<bool>) -> Result<AttachmentCleanup, String> {
+    // Leaving the flag out only previews
+    let dry_run = dry_run.unwrap_or(true);
+    log_info!("Cleaning up unreferenced attachments (dry run: {})", dry_run);
     
-    // Remove duplicates
-    attachments.sort();
-    attachments.dedup();
+    let result = state.attachments_manager.cleanup_unreferenced_attachments(dry_run).await
+        .map_err(|e| format!("Failed to cleanup attachments: {}", e));
     
-    attachments
-}
-
-#[tauri::command]
-pub async fn cleanup_unreferenced_attachments(state: State<'_, Arc<AppState>>) -> Result<usize, String> {
-    log_info!("Cleaning up unreferenced attachments");
+    match &result {
+        Ok(cleanup) => log_debug!("Found {} unreferenced attachments (applied: {})", cleanup.attachments.len(), cleanup.applied),
+        Err(e) => log_error!("Error cleaning up attachments: {}", e),
+    }
     
-    state.notes_manager.cleanup_unused_attachments().await
-        .map_err(|e| format!("Failed to cleanup attachments: {}", e))
+    result
 }
 
 // Git Sync Commands
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use anyhow::{Result, Context};
 use std::fs;
+use sha2::{Digest, Sha256};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -23,6 +24,8 @@ pub struct AppConfig {
     pub theme: String,
     #[serde(alias = "gitSync")]
     pub git_sync: Option<GitSyncConfig>,
+    #[serde(alias = "dailyNotes")]
+    pub daily_notes: Option<DailyNotesConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +56,28 @@ pub struct GitSyncConfig {
     pub auth_type: String, // "none", "basic", "ssh"
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DailyNotesConfig {
+    // chrono format string used for the daily note title, e.g. "%Y-%m-%d"
+    #[serde(alias = "titleFormat")]
+    pub title_format: String,
+    // Initial content; supports {{date}}, {{title}} and {{weekday}} placeholders
+    pub template: Option<String>,
+    // Tag added to every daily note, e.g. "daily"
+    pub tag: Option<String>,
+}
+
+impl Default for DailyNotesConfig {
+    fn default() -> Self {
+        Self {
+            title_format: "%Y-%m-%d".to_string(),
+            template: None,
+            tag: Some("daily".to_string()),
+        }
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -70,6 +95,7 @@ impl Default for AppConfig {
             }),
             theme: "light".to_string(),
             git_sync: None,
+            daily_notes: None,
         }
     }
 }
@@ -158,6 +184,15 @@ impl ConfigManager {
         self.config.data_directory.clone()
     }
     
+    // Beside the config, one folder per notes directory so switching vaults
+    // doesn't mix their drafts
+    pub fn get_drafts_directory(&self) -> PathBuf {
+        let config_dir = self.config_path.parent().map(PathBuf::from).unwrap_or_default();
+        let digest = Sha256::digest(self.config.data_directory.to_string_lossy().as_bytes());
+        let key: String = digest.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+        config_dir.join("drafts").join(key)
+    }
+    
     // Removed get_database_path
     
     fn load_config(path: &PathBuf) -> Result<AppConfig> {
@@ -1,9 +1,13 @@
+pub mod attachments;
 pub mod config;
 pub mod logger;
 pub mod models;
 pub mod notes;
 pub mod storage;
 pub mod tags;
+pub mod vault;
+#[cfg(test)]
+mod test_support;
 
 // Re-export the main types for easier access
 pub use config::{AppConfig, ConfigManager};
@@ -11,4 +15,5 @@ pub use logger::*;
 pub use models::*;
 pub use notes::NotesManager;
 pub use storage::FileStorageManager;
-pub use tags::TagsManager;
\ No newline at end of file
+pub use tags::TagsManager;
+pub use attachments::AttachmentsManager;
\ No newline at end of file
@@ -1,14 +1,20 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod attachments;
 mod commands;
 mod config;
 mod logger;
 mod models;
 mod notes;
+mod scheduler;
 mod storage;
 mod sync;
 mod tags;
+mod vault;
+#[cfg(test)]
+mod test_support;
 
+use attachments::AttachmentsManager;
 use commands::{AppState};
 use config::ConfigManager;
 use logger::init_logger;
@@ -53,20 +59,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize file storage
     let notes_directory = config_manager.get_notes_directory();
     let storage_manager = FileStorageManager::new(notes_directory)
-        .map_err(|e| format!("Failed to initialize storage: {}", e))?;
+        .map_err(|e| format!("Failed to initialize storage: {}", e))?
+        .with_drafts_directory(config_manager.get_drafts_directory());
     
     // Initialize managers
-    // Note: storage_manager is cloned because it's used by both managers
+    // Note: storage_manager is cloned because it's shared by all managers
     let notes_manager = NotesManager::new(storage_manager.clone());
-    let tags_manager = TagsManager::new(storage_manager);
+    let tags_manager = TagsManager::new(storage_manager.clone());
+    let attachments_manager = AttachmentsManager::new(storage_manager);
     
     // Create shared application state
     let app_state = Arc::new(AppState {
         notes_manager: Arc::new(notes_manager),
         tags_manager: Arc::new(tags_manager),
+        attachments_manager: Arc::new(attachments_manager),
         config_manager: Arc::new(Mutex::new(config_manager)),
     });
 
+    // Reminder scheduler shares the notes manager with the command handlers
+    let scheduler_notes_manager = app_state.notes_manager.clone();
+
     // Create menu
     let menu = create_menu();
 
@@ -94,20 +106,101 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             commands::sync_external_files,
             commands::is_setup_required,
             commands::mark_setup_complete,
+            // Daily notes commands
+            commands::open_daily_note,
+            commands::get_adjacent_daily_note,
+            commands::get_journal,
+            // Task commands
+            commands::list_tasks,
+            commands::toggle_task,
+            // Custom property commands
+            commands::set_note_property,
+            commands::unset_note_property,
+            commands::get_vault_properties,
+            commands::query_notes_by_property,
+            // Board commands
+            commands::get_board,
+            commands::move_card,
+            commands::get_board_states,
+            commands::set_board_states,
+            // Reminder commands
+            commands::get_reminders,
+            commands::set_reminder,
+            commands::snooze_reminder,
+            commands::dismiss_reminder,
+            // Outline commands
+            commands::get_note_outline,
+            commands::get_note_section,
+            commands::replace_note_section,
+            commands::move_note_section,
+            // Statistics commands
+            commands::get_vault_stats,
+            // Note restructuring commands
+            commands::duplicate_note,
+            commands::merge_notes,
+            commands::split_note,
+            // Pinned note commands
+            commands::pin_note,
+            commands::unpin_note,
+            commands::set_manual_order,
+            commands::reorder_pinned_notes,
+            commands::get_pinned_note_ids,
+            // Archive commands
+            commands::get_archived_notes,
+            commands::archive_note,
+            commands::unarchive_note,
+            commands::archive_notes,
+            commands::unarchive_notes,
+            // Lock commands
+            commands::lock_note,
+            commands::unlock_note,
+            // Encryption commands
+            commands::encrypt_note,
+            commands::decrypt_note,
+            commands::unlock_encrypted_note,
+            commands::lock_encrypted_note,
+            // Version history commands
+            commands::list_note_versions,
+            commands::get_note_version,
+            commands::diff_note_version,
+            commands::restore_note_version,
+            // Draft journal commands
+            commands::save_draft,
+            commands::get_recoverable_drafts,
+            commands::recover_draft,
+            commands::discard_draft,
+            // Activity commands
+            commands::get_activity_feed,
+            commands::undo_last_operation,
+            // Tag rule commands
+            commands::list_tag_rules,
+            commands::save_tag_rule,
+            commands::delete_tag_rule,
+            commands::preview_tag_rule,
+            commands::apply_rules_to_vault,
             // Tag management commands
             commands::create_tag,
             commands::delete_tag,
             commands::rename_tag,
+            commands::update_tag,
+            commands::merge_tags,
+            commands::normalize_tags,
+            commands::sync_inline_tags,
+            commands::get_tag_policy,
+            commands::set_tag_policy,
             commands::add_tag_to_note,
             commands::remove_tag_from_note,
             commands::get_note_tags,
             commands::search_tags,
+            commands::suggest_tags,
             commands::cleanup_unused_tags,
             commands::save_image_to_attachments,
             commands::save_image_for_text,
             commands::get_data_directory,
             commands::add_attachment,
             commands::list_attachments,
+            commands::list_vault_attachments,
+            commands::get_attachment_info,
             commands::delete_attachment,
             commands::open_attachment,
             commands::cleanup_unreferenced_attachments,
@@ -132,7 +225,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             commands::get_remote_commits,
             commands::get_commit_history
         ])
-        .setup(|_app| {
+        .setup(move |app| {
+            scheduler::start_reminder_scheduler(app.handle(), scheduler_notes_manager);
             // #[cfg(debug_assertions)]
             // {
             //     if let Some(window) = app.get_window("main") {
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NoteMetadata {
@@ -16,6 +17,24 @@ pub struct NoteMetadata {
     pub favorite: bool,
     #[serde(default)]
     pub deleted: bool,
+    // Hidden from normal lists but kept out of the trash, so nothing ever purges it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    // Read-only until explicitly unlocked
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+    // Body is ciphertext; see notes::crypto
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
+    // Local date-time of a pending reminder, e.g. "2026-10-20T09:00"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remind: Option<String>,
+    // Template the note was created from, e.g. "daily"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    // Any other front matter keys, kept as user-defined properties
+    #[serde(flatten)]
+    pub properties: BTreeMap<String, serde_yaml::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -28,16 +47,347 @@ pub struct Note {
     pub modified_at: DateTime<Utc>,
     pub is_favorite: bool,
     pub is_deleted: bool,
+    #[serde(default)]
+    pub is_archived: bool,
+    #[serde(default)]
+    pub is_locked: bool,
+    #[serde(default)]
+    pub is_encrypted: bool,
     pub tags: Vec<String>,
+    // `#tags` written in the body
+    #[serde(default)]
+    pub inline_tags: Vec<String>,
     pub has_attachments: bool,
     pub attachments: Vec<String>,
+    pub remind: Option<String>,
+    pub properties: BTreeMap<String, serde_yaml::Value>,
+    pub word_count: usize,
+    pub reading_time: u32, // minutes
+    // Pinned in the list this note was returned from
+    #[serde(default)]
+    pub is_pinned: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Tag {
-    pub id: String,
+    pub id: String, // registry id, or the name for tags that are not registered
+    pub name: String,
+    pub note_count: usize,
+    // Notes carrying this tag or any tag nested below it
+    #[serde(default)]
+    pub total_count: usize,
+    // `note_count` covers active notes only
+    #[serde(default)]
+    pub archived_count: usize,
+    #[serde(default)]
+    pub trashed_count: usize,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub children: Vec<Tag>,
+}
+
+// One tag name that normalization changes
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagRename {
+    pub from: String,
+    pub to: String,
+    pub note_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagNormalization {
+    pub renames: Vec<TagRename>,
+    // Notes that were (or in a preview, would be) rewritten
+    pub updated: Vec<String>,
+    pub skipped_locked: Vec<String>,
+    pub applied: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagSuggestion {
     pub name: String,
+    pub score: f64,
+    // Similarity of the note's words to notes carrying the tag
+    pub term_score: f64,
+    // How often the tag appears alongside the note's current tags
+    pub cooccurrence_score: f64,
+}
+
+// Tags dropped (or, in a dry run, to be dropped) from the vault settings
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagCleanup {
+    pub tags: Vec<String>,
+    pub applied: bool,
+}
+
+// A note an auto-tagging rule would change
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagRulePreview {
+    pub note_id: String,
+    pub title: String,
+    pub added_tags: Vec<String>,
+    // Locked notes are listed but left alone when rules are applied
+    pub locked: bool,
+}
+
+// None leaves a field as is; an empty string clears it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateTagRequest {
+    pub id: String,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyNote {
+    pub date: String, // YYYY-MM-DD
+    pub note: Note,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Task {
+    pub note_id: String,
+    pub note_title: String,
+    pub line: usize, // 1-based line within the note body
+    pub text: String,
+    pub done: bool,
+    pub due: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct TaskFilter {
+    pub done: Option<bool>,
+    pub tag: Option<String>,
+    pub note_id: Option<String>,
+    pub due_before: Option<String>,
+    pub query: Option<String>,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyType {
+    Text,
+    Number,
+    Boolean,
+    Date,
+    DateTime,
+    Url,
+    List,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PropertySummary {
+    pub key: String,
+    pub property_type: PropertyType,
+    pub note_count: usize,
+    pub values: Vec<serde_yaml::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyOperator {
+    Exists,
+    NotExists,
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Contains,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PropertyFilter {
+    pub key: String,
+    pub op: PropertyOperator,
+    pub value: Option<serde_yaml::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PropertyQuery {
+    #[serde(default)]
+    pub filters: Vec<PropertyFilter>,
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub descending: bool,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Board {
+    pub status_field: String,
+    pub columns: Vec<BoardColumn>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BoardColumn {
+    pub state: String,
+    pub notes: Vec<Note>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct BoardFilter {
+    pub tag: Option<String>,
+    pub query: Option<String>,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Reminder {
+    pub note_id: String,
+    pub note_title: String,
+    pub remind_at: String, // RFC 3339
+    pub overdue: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutlineHeading {
+    pub level: u8,
+    pub text: String,
+    pub slug: String,
+    // Byte and 1-based line ranges of the whole section within the note body
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub children: Vec<OutlineHeading>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteSection {
+    pub level: u8,
+    pub heading: String,
+    pub slug: String,
+    pub content: String, // section body without the heading line
+    pub line_start: usize,
+    pub line_end: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteSize {
+    pub id: String,
+    pub title: String,
+    pub word_count: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttachmentSize {
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultStats {
     pub note_count: usize,
+    pub trashed_count: usize,
+    pub tag_count: usize,
+    pub attachment_count: usize,
+    pub attachment_bytes: u64,
+    pub total_words: usize,
+    pub total_characters: usize,
+    // Keyed by local YYYY-MM-DD
+    pub created_per_day: BTreeMap<String, usize>,
+    pub edited_per_day: BTreeMap<String, usize>,
+    pub heatmap: BTreeMap<String, usize>,
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    pub largest_notes: Vec<NoteSize>,
+    pub largest_attachments: Vec<AttachmentSize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteVersion {
+    pub version_id: String,
+    pub created_at: DateTime<Utc>,
+    pub title: String,
+    pub bytes: u64, // compressed size on disk
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteVersionDiff {
+    pub version_id: String,
+    // Unified diff from the version to the current content
+    pub diff: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+// Unsaved editor content found in the draft journal at startup
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DraftRecovery {
+    pub note_id: String,
+    pub note_title: String,
+    pub content: String,
+    pub saved_at: DateTime<Utc>,
+    pub note_modified_at: DateTime<Utc>,
+}
+
+// One note's state around a journaled operation
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteChange {
+    pub note_id: String,
+    pub title: String,
+    pub file_name: String,
+    // None when the operation created (before) or removed (after) the note
+    pub before: Option<NoteMetadata>,
+    pub after: Option<NoteMetadata>,
+    // Body before the operation, kept only when it changed or the file was removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_before: Option<String>,
+}
+
+// Line of the append-only activity journal
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OperationRecord {
+    pub id: String,
+    pub operation: String, // e.g. "rename_tag", "permanently_delete_note", "undo"
+    pub summary: String,
+    pub timestamp: DateTime<Utc>,
+    pub changes: Vec<NoteChange>,
+    // Set on "undo" records
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undoes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActivityEntry {
+    pub id: String,
+    pub operation: String,
+    pub summary: String,
+    pub timestamp: DateTime<Utc>,
+    pub note_ids: Vec<String>,
+    pub note_titles: Vec<String>,
+    pub undone: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UndoResult {
+    pub operation: ActivityEntry,
+    pub restored: Vec<String>,
+    // Notes edited since the operation, left as they are
+    pub skipped: Vec<String>,
+}
+
+// Outcome of an operation applied across many notes
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BulkResult {
+    pub updated: Vec<String>,
+    pub skipped_locked: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,6 +395,8 @@ pub struct CreateNoteRequest {
     pub title: String,
     pub content: Option<String>,
     pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub template: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -54,23 +406,84 @@ pub struct UpdateNoteRequest {
     pub content: Option<String>,
     pub is_favorite: Option<bool>,
     pub tags: Option<Vec<String>>,
+    // Replaces the note's whole property map when present
+    #[serde(default)]
+    pub properties: Option<BTreeMap<String, serde_yaml::Value>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchRequest {
     pub query: String,
     pub tag_filter: Option<String>,
+    // Replaces the title-match ranking when given
+    #[serde(default)]
+    pub sort: Option<NoteSort>,
+}
+
+// Order of a note list after its pins
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteSort {
+    #[default]
+    Modified,
+    Created,
+    Title,
+    // Stored per list by set_manual_order; notes not in it follow, newest edit first
+    Manual,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Attachment {
-    pub id: String,
+    pub path: String, // relative to the notes directory, as notes link to it
     pub file_name: String,
-    pub file_path: String,
-    pub file_size: i64,
-    pub mime_type: Option<String>,
-    pub created_at: String,
-    pub reference_count: i32,
+    pub size: u64,
+    pub mime_type: String,
+    pub hash: String, // SHA-256, hex
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub created_at: DateTime<Utc>,
+    pub reference_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttachmentReference {
+    pub note_id: String,
+    pub title: String,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttachmentInfo {
+    #[serde(flatten)]
+    pub attachment: Attachment,
+    pub referenced_by: Vec<AttachmentReference>,
+}
+
+// Attachments moved (or, in a dry run, to be moved) to the attachment trash
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttachmentCleanup {
+    pub attachments: Vec<String>,
+    pub bytes: u64,
+    pub applied: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentSort {
+    Name,
+    Size,
+    Created,
+    References,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct AttachmentFilter {
+    pub query: Option<String>,
+    pub mime_type: Option<String>, // full type or a prefix such as "image/"
+    pub referenced: Option<bool>,
+    pub sort_by: Option<AttachmentSort>,
+    #[serde(default)]
+    pub descending: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,196 @@
+use super::NotesManager;
+use crate::models::{ActivityEntry, NoteChange, NoteMetadata, OperationRecord, UndoResult};
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashSet;
+
+impl NotesManager {
+    /// Journaled operations, newest first.
+    pub async fn get_activity_feed(&self, limit: usize) -> Result<Vec<ActivityEntry>> {
+        let records = self.storage.read_operations()?;
+        let undone: HashSet<&str> = records.iter().filter_map(|r| r.undoes.as_deref()).collect();
+
+        Ok(records.iter()
+            .rev()
+            .take(limit)
+            .map(|record| activity_entry(record, undone.contains(record.id.as_str())))
+            .collect())
+    }
+
+    /// Reverts the most recent operation that has not been undone yet. Notes
+    /// changed again since then are left alone and reported as skipped.
+    pub async fn undo_last_operation(&self) -> Result<Option<UndoResult>> {
+        let records = self.storage.read_operations()?;
+        let undone: HashSet<&str> = records.iter().filter_map(|r| r.undoes.as_deref()).collect();
+        let target = match records.iter().rev().find(|r| r.undoes.is_none() && !undone.contains(r.id.as_str())) {
+            Some(record) => record,
+            None => return Ok(None),
+        };
+
+        let mut restored = Vec::new();
+        let mut skipped = Vec::new();
+        for change in &target.changes {
+            if self.revert_change(change)? {
+                restored.push(change.note_id.clone());
+            } else {
+                skipped.push(change.note_id.clone());
+            }
+        }
+
+        // Undone operations can't be undone again, so their bodies are of no further use
+        self.storage.forget_operation_bodies(&target.id)?;
+        self.storage.append_operation(&OperationRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            operation: "undo".to_string(),
+            summary: format!("Undid: {}", target.summary),
+            timestamp: Utc::now(),
+            changes: vec![],
+            undoes: Some(target.id.clone()),
+        })?;
+
+        Ok(Some(UndoResult {
+            operation: activity_entry(target, true),
+            restored,
+            skipped,
+        }))
+    }
+
+    // Journals a change to one note as "<verb> '<title>'"; see record_note_change
+    pub(super) fn record_change(&self, operation: &str, verb: &str, change: NoteChange) -> Result<()> {
+        let summary = format!("{} '{}'", verb, change.title);
+        self.storage.record_note_change(operation, summary, change)
+    }
+
+    fn revert_change(&self, change: &NoteChange) -> Result<bool> {
+        let current = self.storage.find_note_file(&change.note_id)?;
+        match (&change.before, &change.after, current) {
+            // Edited by the operation: put the old front matter (and body) back
+            (Some(before), Some(after), Some((file_name, metadata, content))) if same(&metadata, after) => {
+                let content = change.content_before.clone().unwrap_or(content);
+                self.storage.save_note(&file_name, before, &content)?;
+                Ok(true)
+            }
+            // Created by the operation: move it to the trash
+            (None, Some(after), Some((file_name, mut metadata, content))) if same(&metadata, after) => {
+                metadata.deleted = true;
+                metadata.modified = Utc::now().to_rfc3339();
+                self.storage.save_note(&file_name, &metadata, &content)?;
+                Ok(true)
+            }
+            // Removed by the operation: write the file again
+            (Some(before), None, None) => {
+                let file_name = if self.storage.notes_directory.join(&change.file_name).exists() {
+                    let path = self.storage.generate_unique_file_path(&before.title)?;
+                    path.file_name().unwrap().to_string_lossy().to_string()
+                } else {
+                    change.file_name.clone()
+                };
+                let content = change.content_before.clone().unwrap_or_default();
+                self.storage.save_note(&file_name, before, &content)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+// Short summary for operations on a set of notes
+pub(super) fn describe(verb: &str, changes: &[NoteChange]) -> String {
+    match changes {
+        [change] => format!("{} '{}'", verb, change.title),
+        _ => format!("{} {} notes", verb, changes.len()),
+    }
+}
+
+fn same(a: &NoteMetadata, b: &NoteMetadata) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+fn activity_entry(record: &OperationRecord, undone: bool) -> ActivityEntry {
+    ActivityEntry {
+        id: record.id.clone(),
+        operation: record.operation.clone(),
+        summary: record.summary.clone(),
+        timestamp: record.timestamp,
+        note_ids: record.changes.iter().map(|c| c.note_id.clone()).collect(),
+        note_titles: record.changes.iter().map(|c| c.title.clone()).collect(),
+        undone,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::test_vault;
+    use crate::models::UpdateNoteRequest;
+
+    #[tokio::test]
+    async fn test_undo_tag_rename_and_permanent_delete() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+        let tags = vault.tags();
+
+        let note = vault.create_note("Plan", Some("ship it"), &["work"]).await;
+
+        tags.rename_tag("work", "job").await.unwrap();
+        manager.permanently_delete_note(&note.id).await.unwrap();
+        assert!(manager.get_note(&note.id).await.unwrap().is_none());
+
+        let feed = manager.get_activity_feed(10).await.unwrap();
+        assert_eq!(feed.len(), 3);
+        assert_eq!(feed[0].operation, "permanently_delete_note");
+        assert_eq!(feed[2].operation, "create_note");
+
+        let undo = manager.undo_last_operation().await.unwrap().unwrap();
+        assert_eq!(undo.restored, vec![note.id.clone()]);
+        let back = manager.get_note(&note.id).await.unwrap().unwrap();
+        assert_eq!((back.content.as_str(), back.tags.clone()), ("ship it", vec!["job".to_string()]));
+
+        manager.undo_last_operation().await.unwrap().unwrap();
+        assert_eq!(manager.get_note(&note.id).await.unwrap().unwrap().tags, vec!["work".to_string()]);
+        // Undoing the creation moves the note to the trash
+        manager.undo_last_operation().await.unwrap().unwrap();
+        assert!(manager.storage.find_note_file(&note.id).unwrap().unwrap().1.deleted);
+        assert!(manager.undo_last_operation().await.unwrap().is_none());
+
+        let feed = manager.get_activity_feed(10).await.unwrap();
+        assert_eq!(feed.len(), 6);
+        assert!(feed.iter().filter(|e| e.operation != "undo").all(|e| e.undone));
+        // Undone operations keep no bodies, and the journal stays out of git
+        let records = manager.storage.read_operations().unwrap();
+        assert!(records.iter().flat_map(|r| &r.changes).all(|c| c.content_before.is_none()));
+        let activity_dir = vault.path().join(crate::vault::VAULT_DIR_NAME).join("activity");
+        assert!(activity_dir.join("activity.jsonl").exists() && activity_dir.join(".gitignore").exists());
+    }
+
+    #[tokio::test]
+    async fn test_edits_are_journaled_and_coalesced() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let note = vault.create_note("Draft", Some("one"), &[]).await;
+        for content in ["one two", "one two three"] {
+            manager.update_note(UpdateNoteRequest {
+                id: note.id.clone(),
+                title: None,
+                content: Some(content.to_string()),
+                is_favorite: None,
+                tags: None,
+                properties: None,
+            }).await.unwrap();
+        }
+        // Folding appends instead of rewriting; the older line is superseded when read
+        let journal = vault.path().join(crate::vault::VAULT_DIR_NAME).join("activity").join("activity.jsonl");
+        assert_eq!(std::fs::read_to_string(&journal).unwrap().lines().count(), 3);
+        manager.set_reminder(&note.id, "2026-10-20T09:00").await.unwrap();
+
+        let feed = manager.get_activity_feed(10).await.unwrap();
+        let operations: Vec<&str> = feed.iter().map(|e| e.operation.as_str()).collect();
+        assert_eq!(operations, vec!["set_reminder", "update_note", "create_note"]);
+
+        manager.undo_last_operation().await.unwrap().unwrap();
+        assert!(manager.get_note(&note.id).await.unwrap().unwrap().remind.is_none());
+        // Both autosaves are undone together
+        manager.undo_last_operation().await.unwrap().unwrap();
+        assert_eq!(manager.get_note(&note.id).await.unwrap().unwrap().content, "one");
+    }
+}
@@ -0,0 +1,184 @@
+use super::NotesManager;
+use crate::models::{Board, BoardColumn, BoardFilter, Note, NoteChange, UpdateNoteRequest};
+use anyhow::Result;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+
+impl NotesManager {
+    pub async fn get_board(&self, status_field: &str, filter: Option<&BoardFilter>) -> Result<Board> {
+        let states = self.storage.load_vault_settings()?.board(status_field).states;
+        let order_key = order_key(status_field);
+        let query = filter.and_then(|f| f.query.as_ref()).map(|q| q.to_lowercase());
+
+        let mut columns: Vec<BoardColumn> = states.iter()
+            .map(|state| BoardColumn { state: state.clone(), notes: vec![] })
+            .collect();
+        let mut unknown: BTreeMap<String, Vec<Note>> = BTreeMap::new();
+
+        let notes = if filter.is_some_and(|f| f.include_archived) {
+            self.get_live_notes().await?
+        } else {
+            self.get_all_notes().await?
+        };
+        for note in notes {
+            let status = match note.properties.get(status_field).and_then(|v| v.as_str()) {
+                Some(status) if !status.trim().is_empty() => status.trim().to_string(),
+                _ => continue,
+            };
+
+            if let Some(tag) = filter.and_then(|f| f.tag.as_ref()) {
+                if !note.tags.contains(tag) {
+                    continue;
+                }
+            }
+            if let Some(query) = &query {
+                if !note.title.to_lowercase().contains(query) && !note.content.to_lowercase().contains(query) {
+                    continue;
+                }
+            }
+
+            match columns.iter_mut().find(|c| c.state.eq_ignore_ascii_case(&status)) {
+                Some(column) => column.notes.push(note),
+                None => unknown.entry(status).or_default().push(note),
+            }
+        }
+
+        // States that aren't configured still get a column so no card disappears
+        columns.extend(unknown.into_iter().map(|(state, notes)| BoardColumn { state, notes }));
+
+        for column in &mut columns {
+            // Cards without a position keep get_all_notes order after the positioned ones
+            column.notes.sort_by(|a, b| match (card_position(a, &order_key), card_position(b, &order_key)) {
+                (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            });
+        }
+
+        Ok(Board { status_field: status_field.to_string(), columns })
+    }
+
+    /// Moves a card to `state` at `index` within that column, writing status and position to front matter.
+    pub async fn move_card(&self, note_id: &str, status_field: &str, state: &str, index: usize) -> Result<Option<Note>> {
+        let note = match self.get_note(note_id).await? {
+            Some(note) => note,
+            None => return Ok(None),
+        };
+        Self::ensure_unlocked(&note)?;
+
+        let order_key = order_key(status_field);
+        let board = self.get_board(status_field, None).await?;
+        let column = board.columns.into_iter().find(|c| c.state.eq_ignore_ascii_case(state));
+        let state = column.as_ref().map(|c| c.state.clone()).unwrap_or_else(|| state.trim().to_string());
+        let cards: Vec<Note> = column.map(|c| c.notes).unwrap_or_default()
+            .into_iter()
+            .filter(|n| n.id != note_id)
+            .collect();
+        let index = index.min(cards.len());
+        let mut changes = Vec::new();
+
+        let position = if cards.iter().all(|c| card_position(c, &order_key).is_some()) {
+            let before = index.checked_sub(1).and_then(|i| cards.get(i)).and_then(|c| card_position(c, &order_key));
+            let after = cards.get(index).and_then(|c| card_position(c, &order_key));
+            match (before, after) {
+                (Some(b), Some(a)) => (b + a) / 2.0,
+                (Some(b), None) => b + 1.0,
+                (None, Some(a)) => a - 1.0,
+                (None, None) => 0.0,
+            }
+        } else {
+            // First manual move in this column: give every card an explicit position.
+            // Check locks first so a locked card doesn't leave the column half renumbered.
+            let slots: Vec<(&Note, f64)> = cards.iter().enumerate()
+                .map(|(i, card)| (card, if i < index { i } else { i + 1 } as f64))
+                .filter(|(card, slot)| card_position(card, &order_key) != Some(*slot))
+                .collect();
+            for (card, _) in &slots {
+                Self::ensure_unlocked(card)?;
+            }
+            for (card, slot) in slots {
+                changes.extend(self.write_card_position(&card.id, &order_key, slot)?);
+            }
+            index as f64
+        };
+
+        let mut properties = note.properties;
+        properties.insert(status_field.to_string(), Value::String(state.clone()));
+        properties.insert(order_key, position_value(position));
+
+        let (moved, change) = match self.apply_update(UpdateNoteRequest {
+            id: note_id.to_string(),
+            title: None,
+            content: None,
+            is_favorite: None,
+            tags: None,
+            properties: Some(properties),
+        }).await? {
+            Some(updated) => updated,
+            None => return Ok(None),
+        };
+        changes.insert(0, change);
+        let summary = format!("Moved '{}' to {}", moved.title, state);
+        self.storage.record_operation("move_card", summary, changes)?;
+        Ok(Some(moved))
+    }
+
+    // Reordering neighbours is not an edit, so leave their modified time alone
+    fn write_card_position(&self, note_id: &str, order_key: &str, position: f64) -> Result<Option<NoteChange>> {
+        self.change_metadata(note_id, |metadata| {
+            metadata.properties.insert(order_key.to_string(), position_value(position));
+        })
+    }
+}
+
+fn order_key(status_field: &str) -> String {
+    format!("{}_order", status_field)
+}
+
+fn card_position(note: &Note, order_key: &str) -> Option<f64> {
+    note.properties.get(order_key).and_then(|v| v.as_f64())
+}
+
+fn position_value(position: f64) -> Value {
+    if position.fract() == 0.0 {
+        Value::Number((position as i64).into())
+    } else {
+        Value::Number(position.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+
+    fn titles(column: &BoardColumn) -> Vec<&str> {
+        column.notes.iter().map(|n| n.title.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_board_grouping_and_move_card() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let mut ids = Vec::new();
+        for title in ["A", "B", "C"] {
+            let note = vault.create_note(title, Some(""), &[]).await;
+            manager.set_note_property(&note.id, "status", Value::from("todo")).await.unwrap();
+            ids.push(note.id);
+        }
+
+        manager.move_card(&ids[0], "status", "Todo", 0).await.unwrap();
+        manager.move_card(&ids[2], "status", "Todo", 1).await.unwrap();
+        let board = manager.get_board("status", None).await.unwrap();
+        assert_eq!(board.columns.len(), 3);
+        assert_eq!(titles(&board.columns[0]), vec!["A", "C", "B"]);
+
+        let moved = manager.move_card(&ids[1], "status", "doing", 0).await.unwrap().unwrap();
+        assert_eq!(moved.properties.get("status"), Some(&Value::from("Doing")));
+        let board = manager.get_board("status", None).await.unwrap();
+        assert_eq!(titles(&board.columns[0]), vec!["A", "C"]);
+        assert_eq!(titles(&board.columns[1]), vec!["B"]);
+    }
+}
@@ -0,0 +1,352 @@
+use super::NotesManager;
+use crate::attachments::extract_attachment_references;
+use crate::models::{Note, NoteMetadata};
+use anyhow::Result;
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use std::time::{Duration, Instant};
+use zeroize::Zeroize;
+
+const ARMOR_BEGIN: &str = "-----BEGIN XNOTE ENCRYPTED NOTE-----";
+const ARMOR_END: &str = "-----END XNOTE ENCRYPTED NOTE-----";
+const ARMOR_WIDTH: usize = 64;
+
+// Payload layout: version | salt | nonce | ciphertext
+const FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+// How long an unlocked note stays readable without asking again
+const SESSION_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+// Key for an unlocked note, kept in memory only
+pub(super) struct SessionKey {
+    key: [u8; KEY_LEN],
+    salt: [u8; SALT_LEN],
+    expires_at: Instant,
+}
+
+// Covers forgotten, expired and replaced sessions alike
+impl Drop for SessionKey {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl NotesManager {
+    /// Encrypts the note body with a key derived from `passphrase`; the front matter stays readable.
+    pub async fn encrypt_note(&self, note_id: &str, passphrase: &str) -> Result<Option<Note>> {
+        let (file_name, mut metadata, content) = match self.storage.find_note_file(note_id)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        if metadata.locked {
+            return Err(super::NoteLockedError::new(note_id).into());
+        }
+        if metadata.encrypted {
+            return Err(anyhow::anyhow!("Note {} is already encrypted", note_id));
+        }
+        validate_passphrase(passphrase)?;
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = derive_key(passphrase, &salt)?;
+
+        metadata.encrypted = true;
+        sync_body_attachments(&mut metadata, None, &content);
+        let armored = seal(&key, &salt, &content)?;
+        self.storage.save_note(&file_name, &metadata, &armored)?;
+        // Earlier versions and drafts are plain text
+        self.storage.delete_history(note_id)?;
+        self.storage.retain_drafts(|d| d.note_id != note_id)?;
+        self.storage.forget_note_bodies(note_id)?;
+        self.forget_session(note_id);
+
+        Ok(Some(self.metadata_to_note(metadata, armored, file_name, &self.tag_policy()?)))
+    }
+
+    /// Removes encryption for good, writing the plain body back to disk.
+    pub async fn decrypt_note(&self, note_id: &str, passphrase: &str) -> Result<Option<Note>> {
+        let (file_name, mut metadata, content) = match self.storage.find_note_file(note_id)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        if metadata.locked {
+            return Err(super::NoteLockedError::new(note_id).into());
+        }
+        if !metadata.encrypted {
+            return Err(anyhow::anyhow!("Note {} is not encrypted", note_id));
+        }
+
+        let (_, plaintext) = unlock_armor(&content, passphrase)?;
+        metadata.encrypted = false;
+        self.storage.save_note(&file_name, &metadata, &plaintext)?;
+        // Drops the armored body just snapshotted; history starts over from here
+        self.storage.delete_history(note_id)?;
+        self.forget_session(note_id);
+
+        Ok(Some(self.metadata_to_note(metadata, plaintext, file_name, &self.tag_policy()?)))
+    }
+
+    /// Checks the passphrase and keeps the note readable and editable for the session timeout.
+    pub async fn unlock_encrypted_note(&self, note_id: &str, passphrase: &str) -> Result<Option<Note>> {
+        let (file_name, metadata, content) = match self.storage.find_note_file(note_id)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        if !metadata.encrypted {
+            return Err(anyhow::anyhow!("Note {} is not encrypted", note_id));
+        }
+
+        let (session, _) = unlock_armor(&content, passphrase)?;
+        self.sessions.lock().unwrap().insert(note_id.to_string(), session);
+
+        Ok(Some(self.metadata_to_note(metadata, content, file_name, &self.tag_policy()?)))
+    }
+
+    pub async fn lock_encrypted_note(&self, note_id: &str) -> Result<bool> {
+        Ok(self.forget_session(note_id))
+    }
+
+    fn forget_session(&self, note_id: &str) -> bool {
+        self.sessions.lock().unwrap().remove(note_id).is_some()
+    }
+
+    // Duplicating, merging, splitting and moving sections write bodies into
+    // ordinary notes, so they refuse encrypted notes whether or not unlocked
+    pub(super) fn ensure_not_encrypted(note: &Note) -> Result<()> {
+        if note.is_encrypted {
+            return Err(anyhow::anyhow!("note is encrypted: {} (decrypt it first)", note.id));
+        }
+        Ok(())
+    }
+
+    // Plain body of an encrypted note, if it is unlocked in this session
+    pub(super) fn open_with_session(&self, note_id: &str, armored: &str) -> Option<String> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.get(note_id)?;
+        if session.expires_at <= Instant::now() {
+            sessions.remove(note_id);
+            return None;
+        }
+
+        let (_, nonce, ciphertext) = parse_armor(armored)?;
+        open(&session.key, &nonce, &ciphertext).ok()
+    }
+
+    // Encrypts a new body with the session key, failing if the note isn't unlocked
+    pub(super) fn seal_with_session(&self, note_id: &str, plaintext: &str) -> Result<String> {
+        let mut sessions = self.sessions.lock().unwrap();
+        match sessions.get(note_id) {
+            Some(session) if session.expires_at > Instant::now() => seal(&session.key, &session.salt, plaintext),
+            Some(_) => {
+                sessions.remove(note_id);
+                Err(anyhow::anyhow!("note is encrypted: {} (session expired)", note_id))
+            }
+            None => Err(anyhow::anyhow!("note is encrypted: {}", note_id)),
+        }
+    }
+}
+
+// Attachment cleanup can't read encrypted bodies, so the files they link to
+// are listed in front matter instead. Links gone since the `previous` body are
+// dropped; attachments added to the note by hand stay.
+pub(super) fn sync_body_attachments(metadata: &mut NoteMetadata, previous: Option<&str>, plaintext: &str) {
+    let linked = extract_attachment_references(plaintext);
+    if let Some(previous) = previous {
+        let unlinked: Vec<String> = extract_attachment_references(previous)
+            .into_iter()
+            .filter(|path| !linked.contains(path))
+            .collect();
+        metadata.attachments.retain(|path| !unlinked.contains(path));
+    }
+    for path in linked {
+        if !metadata.attachments.contains(&path) {
+            metadata.attachments.push(path);
+        }
+    }
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.chars().count() < 8 {
+        return Err(anyhow::anyhow!("Passphrase must be at least 8 characters"));
+    }
+    Ok(())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; KEY_LEN]> {
+    let mut key = [0u8; KEY_LEN];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+fn seal(key: &[u8; KEY_LEN], salt: &[u8; SALT_LEN], plaintext: &str) -> Result<String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+
+    let mut payload = Vec::with_capacity(1 + SALT_LEN + NONCE_LEN + ciphertext.len());
+    payload.push(FORMAT_VERSION);
+    payload.extend_from_slice(salt);
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&ciphertext);
+
+    let encoded = BASE64.encode(payload);
+    let mut armored = String::from(ARMOR_BEGIN);
+    armored.push('\n');
+    for line in encoded.as_bytes().chunks(ARMOR_WIDTH) {
+        armored.push_str(std::str::from_utf8(line).unwrap_or_default());
+        armored.push('\n');
+    }
+    armored.push_str(ARMOR_END);
+    armored.push('\n');
+    Ok(armored)
+}
+
+fn parse_armor(armored: &str) -> Option<([u8; SALT_LEN], [u8; NONCE_LEN], Vec<u8>)> {
+    let body = armored.trim().strip_prefix(ARMOR_BEGIN)?.strip_suffix(ARMOR_END)?;
+    let encoded: String = body.split_whitespace().collect();
+    let payload = BASE64.decode(encoded).ok()?;
+    if payload.len() <= 1 + SALT_LEN + NONCE_LEN || payload[0] != FORMAT_VERSION {
+        return None;
+    }
+
+    let salt = payload[1..1 + SALT_LEN].try_into().ok()?;
+    let nonce = payload[1 + SALT_LEN..1 + SALT_LEN + NONCE_LEN].try_into().ok()?;
+    Some((salt, nonce, payload[1 + SALT_LEN + NONCE_LEN..].to_vec()))
+}
+
+fn open(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], ciphertext: &[u8]) -> Result<String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let plaintext = cipher.decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow::anyhow!("Wrong passphrase or corrupted note"))?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+fn unlock_armor(armored: &str, passphrase: &str) -> Result<(SessionKey, String)> {
+    let (salt, nonce, ciphertext) = parse_armor(armored)
+        .ok_or_else(|| anyhow::anyhow!("Encrypted note body is malformed"))?;
+    let key = derive_key(passphrase, &salt)?;
+    let plaintext = open(&key, &nonce, &ciphertext)?;
+    Ok((SessionKey { key, salt, expires_at: Instant::now() + SESSION_TIMEOUT }, plaintext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+    use crate::models::UpdateNoteRequest;
+
+    fn files_containing(dir: &std::path::Path, text: &str) -> Vec<std::path::PathBuf> {
+        let mut found = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                found.extend(files_containing(&path, text));
+            } else if std::fs::read_to_string(&path).is_ok_and(|c| c.contains(text)) {
+                found.push(path);
+            }
+        }
+        found
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_unlock_and_edit() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let note = vault.create_note("Accounts", Some("pin 1234"), &["private"]).await;
+
+        manager.update_note(UpdateNoteRequest {
+            id: note.id.clone(),
+            title: None,
+            content: Some("pin 1234, card 9876".to_string()),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        }).await.unwrap();
+        assert!(!manager.storage.list_history(&note.id).unwrap().is_empty());
+
+        let encrypted = manager.encrypt_note(&note.id, "correct horse").await.unwrap().unwrap();
+        assert!(manager.storage.list_history(&note.id).unwrap().is_empty());
+        assert!(encrypted.is_encrypted);
+        assert!(encrypted.content.starts_with(ARMOR_BEGIN));
+        // The armor isn't counted as words
+        assert_eq!(encrypted.word_count, 0);
+        assert_eq!(manager.get_vault_stats(None).await.unwrap().total_words, 0);
+        let raw = std::fs::read_to_string(vault.path().join(&note.file_path)).unwrap();
+        assert!(raw.contains("encrypted: true") && !raw.contains("1234"));
+        let history_dir = vault.path().join(".xnote").join("history");
+        assert!(!history_dir.join(&note.id).exists());
+        // Nothing in the vault, activity journal included, keeps the plain text
+        assert!(files_containing(vault.path(), "1234").is_empty());
+        assert!(manager.search_notes("1234", None, None).await.unwrap().is_empty());
+
+        assert!(manager.unlock_encrypted_note(&note.id, "wrong pass").await.is_err());
+        let unlocked = manager.unlock_encrypted_note(&note.id, "correct horse").await.unwrap().unwrap();
+        assert_eq!(unlocked.content, "pin 1234, card 9876");
+        assert!(unlocked.word_count > 0);
+        let stats = manager.get_vault_stats(None).await.unwrap();
+        assert_eq!((stats.total_words, stats.largest_notes.len()), (0, 0));
+
+        let edit = UpdateNoteRequest {
+            id: note.id.clone(),
+            title: None,
+            content: Some("pin 5678 ![card](attachments/card.png)".to_string()),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        };
+        let edited = manager.update_note(edit).await.unwrap().unwrap();
+        assert_eq!(edited.content, "pin 5678 ![card](attachments/card.png)");
+        // Attachment cleanup only sees front matter
+        assert_eq!(edited.attachments, vec!["attachments/card.png".to_string()]);
+        let edit = UpdateNoteRequest {
+            id: note.id.clone(),
+            title: None,
+            content: Some("pin 5678 ![scan](attachments/scan.png)".to_string()),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        };
+        let relinked = manager.update_note(edit).await.unwrap().unwrap();
+        assert_eq!(relinked.attachments, vec!["attachments/scan.png".to_string()]);
+        let edit = UpdateNoteRequest {
+            id: note.id.clone(),
+            title: None,
+            content: Some("pin 5678 ![card](attachments/card.png)".to_string()),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        };
+        manager.update_note(edit).await.unwrap();
+        assert!(manager.storage.list_history(&note.id).unwrap().is_empty());
+        // Unlocked bodies are still kept out of search
+        assert!(manager.search_notes("5678", None, None).await.unwrap().is_empty());
+
+        // Restructuring would copy the body into plain notes
+        let other = vault.create_note("Inbox", Some("# Keys\nsafe"), &[]).await;
+        assert!(manager.duplicate_note(&note.id).await.is_err());
+        assert!(manager.merge_notes(std::slice::from_ref(&note.id), &other.id).await.is_err());
+        assert!(manager.merge_notes(std::slice::from_ref(&other.id), &note.id).await.is_err());
+        assert!(manager.split_note(&note.id, 1).await.is_err());
+        assert!(manager.move_note_section(&other.id, "Keys", &note.id).await.is_err());
+        assert_eq!(manager.get_all_notes().await.unwrap().len(), 2);
+
+        assert!(manager.lock_encrypted_note(&note.id).await.unwrap());
+        assert!(manager.get_note(&note.id).await.unwrap().unwrap().content.starts_with(ARMOR_BEGIN));
+
+        let decrypted = manager.decrypt_note(&note.id, "correct horse").await.unwrap().unwrap();
+        assert_eq!(decrypted.content, "pin 5678 ![card](attachments/card.png)");
+        assert!(!decrypted.is_encrypted);
+        assert!(manager.storage.list_history(&note.id).unwrap().is_empty());
+    }
+}
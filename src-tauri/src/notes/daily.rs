@@ -0,0 +1,162 @@
+use super::NotesManager;
+use crate::config::DailyNotesConfig;
+use crate::models::{CreateNoteRequest, DailyNote, Note};
+use crate::storage::note_change;
+use anyhow::Result;
+use chrono::{Local, NaiveDate, TimeZone, Utc};
+
+// Recorded as the `template` of daily notes, so tag rules can match them
+const DAILY_TEMPLATE: &str = "daily";
+
+impl NotesManager {
+    /// Finds the daily note for `date`, creating it from the configured template if needed.
+    pub async fn open_daily_note(&self, date: NaiveDate, config: &DailyNotesConfig) -> Result<DailyNote> {
+        if let Some(existing) = self.find_daily_note(date, config).await? {
+            return Ok(existing);
+        }
+
+        let title = date.format(&config.title_format).to_string();
+        let content = render_daily_template(config.template.as_deref(), date, &title);
+        let tags = config.tag.iter().filter(|t| !t.trim().is_empty()).cloned().collect();
+
+        let (note, change) = self.save_new_note(CreateNoteRequest {
+            title,
+            content: Some(content),
+            tags: Some(tags),
+            template: Some(DAILY_TEMPLATE.to_string()),
+        })?;
+
+        // Backfilled dailies should sort by the day they describe, not the day they were written
+        let (note, change) = if date != Local::now().date_naive() {
+            match self.set_note_created(&note.id, date).await? {
+                Some(note) => {
+                    let change = note_change(None, self.storage.find_note_file(&note.id)?.as_ref());
+                    (note, change)
+                }
+                None => (note, change),
+            }
+        } else {
+            (note, change)
+        };
+        self.record_change("create_note", "Created", change)?;
+
+        Ok(DailyNote { date: date.format("%Y-%m-%d").to_string(), note })
+    }
+
+    pub async fn find_daily_note(&self, date: NaiveDate, config: &DailyNotesConfig) -> Result<Option<DailyNote>> {
+        let journal = self.get_journal(config).await?;
+        let key = date.format("%Y-%m-%d").to_string();
+        Ok(journal.into_iter().find(|d| d.date == key))
+    }
+
+    /// Returns the closest existing daily note before (`forward == false`) or after `date`.
+    pub async fn get_adjacent_daily_note(&self, date: NaiveDate, forward: bool, config: &DailyNotesConfig) -> Result<Option<DailyNote>> {
+        let key = date.format("%Y-%m-%d").to_string();
+        let journal = self.get_journal(config).await?;
+
+        // Journal is newest first
+        if forward {
+            Ok(journal.into_iter().rev().find(|d| d.date > key))
+        } else {
+            Ok(journal.into_iter().find(|d| d.date < key))
+        }
+    }
+
+    /// Lists every daily note, archived ones included, newest first.
+    pub async fn get_journal(&self, config: &DailyNotesConfig) -> Result<Vec<DailyNote>> {
+        let notes = self.get_live_notes().await?;
+        let mut journal: Vec<DailyNote> = Vec::new();
+
+        for note in notes {
+            if let Some(date) = parse_daily_title(&note.title, &config.title_format) {
+                let key = date.format("%Y-%m-%d").to_string();
+                // Keep the oldest note when two files claim the same day
+                match journal.iter_mut().find(|d| d.date == key) {
+                    Some(entry) if entry.note.created_at > note.created_at => entry.note = note,
+                    Some(_) => {}
+                    None => journal.push(DailyNote { date: key, note }),
+                }
+            }
+        }
+
+        journal.sort_by(|a, b| b.date.cmp(&a.date));
+        Ok(journal)
+    }
+
+    async fn set_note_created(&self, id: &str, date: NaiveDate) -> Result<Option<Note>> {
+        let created = match Local.from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap()).single() {
+            Some(dt) => dt.with_timezone(&Utc),
+            None => return Ok(None),
+        };
+
+        self.update_metadata(id, |metadata| metadata.created = created.to_rfc3339())
+    }
+}
+
+fn parse_daily_title(title: &str, format: &str) -> Option<NaiveDate> {
+    let date = NaiveDate::parse_from_str(title.trim(), format).ok()?;
+    // Reject titles that only parse loosely, e.g. "2026-1-5" against "%Y-%m-%d"
+    if date.format(format).to_string() == title.trim() {
+        Some(date)
+    } else {
+        None
+    }
+}
+
+fn render_daily_template(template: Option<&str>, date: NaiveDate, title: &str) -> String {
+    match template {
+        Some(template) => template
+            .replace("{{date}}", &date.format("%Y-%m-%d").to_string())
+            .replace("{{title}}", title)
+            .replace("{{weekday}}", &date.format("%A").to_string()),
+        None => format!("# {}\n\n", title),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_daily_title() {
+        assert_eq!(parse_daily_title("2026-10-18", "%Y-%m-%d"), Some(date("2026-10-18")));
+        assert_eq!(parse_daily_title("Journal 2026.10.18", "Journal %Y.%m.%d"), Some(date("2026-10-18")));
+        assert_eq!(parse_daily_title("Meeting notes", "%Y-%m-%d"), None);
+    }
+
+    #[test]
+    fn test_render_daily_template() {
+        let rendered = render_daily_template(Some("# {{title}}\n{{weekday}} {{date}}"), date("2026-10-18"), "Oct 18");
+        assert_eq!(rendered, "# Oct 18\nSunday 2026-10-18");
+    }
+
+    #[tokio::test]
+    async fn test_open_daily_note_is_idempotent() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+        let config = DailyNotesConfig::default();
+
+        let first = manager.open_daily_note(date("2026-01-05"), &config).await.unwrap();
+        let second = manager.open_daily_note(date("2026-01-05"), &config).await.unwrap();
+        assert_eq!(first.note.id, second.note.id);
+        assert_eq!(first.note.tags, vec!["daily".to_string()]);
+        assert_eq!(first.note.created_at.with_timezone(&Local).date_naive(), date("2026-01-05"));
+
+        manager.open_daily_note(date("2026-01-07"), &config).await.unwrap();
+        let next = manager.get_adjacent_daily_note(date("2026-01-05"), true, &config).await.unwrap();
+        assert_eq!(next.unwrap().date, "2026-01-07");
+        let previous = manager.get_adjacent_daily_note(date("2026-01-05"), false, &config).await.unwrap();
+        assert!(previous.is_none());
+
+        // An archived daily note still owns its day
+        manager.archive_note(&first.note.id).await.unwrap();
+        let reopened = manager.open_daily_note(date("2026-01-05"), &config).await.unwrap();
+        assert_eq!(reopened.note.id, first.note.id);
+        assert_eq!(manager.get_journal(&config).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,145 @@
+use super::NotesManager;
+use crate::models::{DraftRecovery, Note, UpdateNoteRequest};
+use anyhow::Result;
+
+impl NotesManager {
+    /// Journals unsaved editor content. Returns false when the draft was not
+    /// kept because the note is encrypted.
+    pub async fn save_draft(&self, note_id: &str, content: &str) -> Result<bool> {
+        if self.is_encrypted_on_disk(note_id)? {
+            return Ok(false);
+        }
+        self.storage.append_draft(note_id, content)?;
+        Ok(true)
+    }
+
+    // Reads the flag from the note's file each time, since encryption can change
+    // under a running editor; only the file's name is remembered
+    fn is_encrypted_on_disk(&self, note_id: &str) -> Result<bool> {
+        let known = self.draft_files.lock().unwrap().get(note_id).cloned();
+        if let Some(file_name) = known {
+            if let Ok((metadata, _)) = self.storage.parse_note(&file_name) {
+                if metadata.id == note_id {
+                    return Ok(metadata.encrypted);
+                }
+            }
+        }
+        // New to this session, or renamed since
+        match self.storage.find_note_file(note_id)? {
+            Some((file_name, metadata, _)) => {
+                self.draft_files.lock().unwrap().insert(note_id.to_string(), file_name);
+                Ok(metadata.encrypted)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Drafts newer than their saved note and different from it. Everything
+    /// else is dropped from the journal.
+    pub async fn get_recoverable_drafts(&self) -> Result<Vec<DraftRecovery>> {
+        // Archived notes can have drafts too; trashed ones are not offered
+        let notes = self.load_notes(|m| !m.deleted)?;
+        let mut recoverable = Vec::new();
+
+        for draft in self.storage.latest_drafts()?.into_values() {
+            if let Some(note) = notes.iter().find(|n| n.id == draft.note_id) {
+                if !note.is_encrypted && draft.saved_at > note.modified_at && draft.content != note.content {
+                    recoverable.push(DraftRecovery {
+                        note_id: draft.note_id,
+                        note_title: note.title.clone(),
+                        content: draft.content,
+                        saved_at: draft.saved_at,
+                        note_modified_at: note.modified_at,
+                    });
+                }
+            }
+        }
+
+        let keep: Vec<String> = recoverable.iter().map(|d| d.note_id.clone()).collect();
+        self.storage.retain_drafts(|d| keep.contains(&d.note_id))?;
+
+        recoverable.sort_by_key(|d| std::cmp::Reverse(d.saved_at));
+        Ok(recoverable)
+    }
+
+    /// Writes the newest draft into the note and clears it from the journal.
+    pub async fn recover_draft(&self, note_id: &str) -> Result<Option<Note>> {
+        let draft = match self.storage.latest_drafts()?.remove(note_id) {
+            Some(draft) => draft,
+            None => return Ok(None),
+        };
+
+        let note = self.update_and_record("recover_draft", "Recovered a draft of", UpdateNoteRequest {
+            id: note_id.to_string(),
+            title: None,
+            content: Some(draft.content),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        }).await?;
+
+        self.discard_draft(note_id).await?;
+        Ok(note)
+    }
+
+    pub async fn discard_draft(&self, note_id: &str) -> Result<()> {
+        self.storage.retain_drafts(|d| d.note_id != note_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+    use crate::storage::FileStorageManager;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_drafts_newer_than_note_are_recoverable() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let note = vault.create_note("Essay", Some("saved"), &[]).await;
+        let other = vault.create_note("Other", Some("same"), &[]).await;
+
+        manager.save_draft(&note.id, "saved and more").await.unwrap();
+        manager.save_draft(&note.id, "saved and even more").await.unwrap();
+        // Identical to what is on disk, so nothing to recover
+        manager.save_draft(&other.id, "same").await.unwrap();
+
+        let drafts = manager.get_recoverable_drafts().await.unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].content, "saved and even more");
+
+        let recovered = manager.recover_draft(&note.id).await.unwrap().unwrap();
+        assert_eq!(recovered.content, "saved and even more");
+        assert!(manager.get_recoverable_drafts().await.unwrap().is_empty());
+        assert!(manager.recover_draft(&note.id).await.unwrap().is_none());
+
+        // A fresh manager has never listed the note, yet still refuses its draft
+        manager.encrypt_note(&other.id, "correct horse").await.unwrap();
+        let restarted = NotesManager::new(FileStorageManager::new(vault.path().to_path_buf()).unwrap());
+        assert!(!restarted.save_draft(&other.id, "plain secret").await.unwrap());
+
+        // Encrypting after the note was drafted is still noticed
+        restarted.decrypt_note(&other.id, "correct horse").await.unwrap();
+        assert!(restarted.save_draft(&other.id, "plain").await.unwrap());
+        restarted.encrypt_note(&other.id, "correct horse").await.unwrap();
+        assert!(!restarted.save_draft(&other.id, "plain secret").await.unwrap());
+
+        // Without an app data folder the journal stays local to this machine
+        manager.save_draft(&note.id, "unsaved").await.unwrap();
+        let drafts_dir = vault.path().join(crate::vault::VAULT_DIR_NAME).join("drafts");
+        assert!(drafts_dir.join("drafts.jsonl").exists());
+        assert_eq!(std::fs::read_to_string(drafts_dir.join(".gitignore")).unwrap(), "*\n");
+
+        // With one, the journal moves there and leaves the notes folder
+        let data_dir = TempDir::new().unwrap();
+        let storage = FileStorageManager::new(vault.path().to_path_buf()).unwrap()
+            .with_drafts_directory(data_dir.path().join("drafts"));
+        let moved = NotesManager::new(storage);
+        assert_eq!(moved.get_recoverable_drafts().await.unwrap()[0].content, "unsaved");
+        assert!(!drafts_dir.join("drafts.jsonl").exists());
+        assert!(data_dir.path().join("drafts").join("drafts.jsonl").exists());
+    }
+}
@@ -0,0 +1,139 @@
+use super::NotesManager;
+use crate::models::{Note, NoteVersion, NoteVersionDiff, UpdateNoteRequest};
+use crate::storage::parse_note_text;
+use anyhow::Result;
+use similar::{ChangeTag, TextDiff};
+use std::fs;
+
+impl NotesManager {
+    /// Lists stored snapshots of a note, newest first.
+    pub async fn list_note_versions(&self, note_id: &str) -> Result<Vec<NoteVersion>> {
+        let mut versions = Vec::new();
+        for entry in self.storage.list_history(note_id)? {
+            let title = match self.storage.read_history(note_id, &entry.version_id)? {
+                Some(text) => parse_note_text(&text).map(|(m, _)| m.title).unwrap_or_default(),
+                None => continue,
+            };
+            versions.push(NoteVersion {
+                version_id: entry.version_id,
+                created_at: entry.created_at,
+                title,
+                bytes: entry.bytes,
+            });
+        }
+        Ok(versions)
+    }
+
+    pub async fn get_note_version(&self, note_id: &str, version_id: &str) -> Result<Option<Note>> {
+        let text = match self.storage.read_history(note_id, version_id)? {
+            Some(text) => text,
+            None => return Ok(None),
+        };
+        let (metadata, content) = parse_note_text(&text)?;
+        let file_path = self.storage.find_note_file(note_id)?.map(|(name, _, _)| name).unwrap_or_default();
+        Ok(Some(self.metadata_to_note(metadata, content, file_path, &self.tag_policy()?)))
+    }
+
+    pub async fn diff_note_version(&self, note_id: &str, version_id: &str) -> Result<Option<NoteVersionDiff>> {
+        let (version, current) = match (self.get_note_version(note_id, version_id).await?, self.get_note(note_id).await?) {
+            (Some(version), Some(current)) => (version, current),
+            _ => return Ok(None),
+        };
+
+        let diff = TextDiff::from_lines(&version.content, &current.content);
+        let mut additions = 0;
+        let mut deletions = 0;
+        for change in diff.iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Insert => additions += 1,
+                ChangeTag::Delete => deletions += 1,
+                ChangeTag::Equal => {}
+            }
+        }
+        let unified = diff.unified_diff().context_radius(3).header(version_id, "current").to_string();
+
+        Ok(Some(NoteVersionDiff { version_id: version_id.to_string(), diff: unified, additions, deletions }))
+    }
+
+    /// Brings back a version's title and body. The current file is snapshotted
+    /// first, so a restore can itself be undone.
+    pub async fn restore_note_version(&self, note_id: &str, version_id: &str) -> Result<Option<Note>> {
+        let version = match self.get_note_version(note_id, version_id).await? {
+            Some(version) => version,
+            None => return Ok(None),
+        };
+        let (file_name, metadata, _) = match self.storage.find_note_file(note_id)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        if metadata.locked {
+            return Err(super::NoteLockedError::new(note_id).into());
+        }
+        if metadata.encrypted {
+            return Err(anyhow::anyhow!("note is encrypted: {} (versions are not kept)", note_id));
+        }
+
+        let current = fs::read_to_string(self.storage.notes_directory.join(&file_name))?;
+        self.storage.snapshot_note(note_id, &current, true)?;
+
+        self.update_and_record("restore_note_version", "Restored an earlier version of", UpdateNoteRequest {
+            id: note_id.to_string(),
+            title: Some(version.title),
+            content: Some(version.content),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+
+    fn content_update(id: &str, content: &str) -> UpdateNoteRequest {
+        UpdateNoteRequest {
+            id: id.to_string(),
+            title: None,
+            content: Some(content.to_string()),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_versions_are_throttled_and_restorable() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let note = vault.create_note("Draft", Some("first\n"), &[]).await;
+
+        manager.update_note(content_update(&note.id, "second\n")).await.unwrap();
+        // Inside the five minute window, so no new snapshot
+        manager.update_note(content_update(&note.id, "third\n")).await.unwrap();
+
+        let versions = manager.list_note_versions(&note.id).await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].title, "Draft");
+        let first = manager.get_note_version(&note.id, &versions[0].version_id).await.unwrap().unwrap();
+        assert_eq!(first.content, "first\n");
+
+        let diff = manager.diff_note_version(&note.id, &versions[0].version_id).await.unwrap().unwrap();
+        assert_eq!((diff.additions, diff.deletions), (1, 1));
+        assert!(diff.diff.contains("-first") && diff.diff.contains("+third"));
+
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let restored = manager.restore_note_version(&note.id, &versions[0].version_id).await.unwrap().unwrap();
+        assert_eq!(restored.content, "first\n");
+        // The overwritten content was kept as its own version
+        let versions = manager.list_note_versions(&note.id).await.unwrap();
+        assert_eq!(versions.len(), 2);
+        let previous = manager.get_note_version(&note.id, &versions[0].version_id).await.unwrap().unwrap();
+        assert_eq!(previous.content, "third\n");
+
+        manager.permanently_delete_note(&note.id).await.unwrap();
+        assert!(manager.list_note_versions(&note.id).await.unwrap().is_empty());
+    }
+}
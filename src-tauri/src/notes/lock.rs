@@ -0,0 +1,105 @@
+use super::NotesManager;
+use crate::models::Note;
+use anyhow::Result;
+use std::fmt;
+
+/// Returned (inside anyhow) when a locked note would be modified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteLockedError {
+    pub note_id: String,
+}
+
+impl fmt::Display for NoteLockedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "note is locked: {}", self.note_id)
+    }
+}
+
+impl std::error::Error for NoteLockedError {}
+
+impl NoteLockedError {
+    pub fn new(note_id: &str) -> Self {
+        Self { note_id: note_id.to_string() }
+    }
+}
+
+impl NotesManager {
+    pub async fn lock_note(&self, note_id: &str) -> Result<Option<Note>> {
+        self.set_locked(note_id, true)
+    }
+
+    pub async fn unlock_note(&self, note_id: &str) -> Result<Option<Note>> {
+        self.set_locked(note_id, false)
+    }
+
+    // The one front matter write that bypasses the lock check
+    fn set_locked(&self, note_id: &str, locked: bool) -> Result<Option<Note>> {
+        let (file_name, mut metadata, content) = match self.storage.find_note_file(note_id)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        metadata.locked = locked;
+        self.storage.save_note(&file_name, &metadata, &content)?;
+        Ok(Some(self.metadata_to_note(metadata, content, file_name, &self.tag_policy()?)))
+    }
+
+    pub(super) fn ensure_unlocked(note: &Note) -> Result<()> {
+        if note.is_locked {
+            return Err(NoteLockedError::new(&note.id).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+    use crate::models::UpdateNoteRequest;
+
+    fn content_update(id: &str, content: &str) -> UpdateNoteRequest {
+        UpdateNoteRequest {
+            id: id.to_string(),
+            title: None,
+            content: Some(content.to_string()),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_locked_note_rejects_edits_until_unlocked() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let note = vault.create_note("Procedure", Some("step 1"), &[]).await;
+        manager.add_attachment_to_note(&note.id, "attachments/spec.pdf").await.unwrap();
+
+        assert!(manager.lock_note(&note.id).await.unwrap().unwrap().is_locked);
+        let err = manager.update_note(content_update(&note.id, "changed")).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<NoteLockedError>(), Some(&NoteLockedError::new(&note.id)));
+        assert!(manager.remove_attachment_from_note(&note.id, "attachments/spec.pdf").await.is_err());
+
+        // Favouriting isn't an edit
+        let mut favorite = content_update(&note.id, "");
+        favorite.content = None;
+        favorite.is_favorite = Some(true);
+        assert!(manager.update_note(favorite).await.unwrap().unwrap().is_favorite);
+
+        // Archiving, trashing and reminders are refused too; bulk archive skips the note
+        let other = vault.create_note("Scratch", None, &[]).await;
+        let archived = manager.set_notes_archived(&[note.id.clone(), other.id.clone()], true).await.unwrap();
+        assert_eq!(archived.updated, vec![other.id.clone()]);
+        assert_eq!(archived.skipped_locked, vec![note.id.clone()]);
+        assert!(manager.archive_note(&note.id).await.unwrap_err().is::<NoteLockedError>());
+        assert!(manager.delete_note(&note.id).await.unwrap_err().is::<NoteLockedError>());
+        assert!(manager.permanently_delete_note(&note.id).await.unwrap_err().is::<NoteLockedError>());
+        assert!(manager.set_reminder(&note.id, "2026-10-20T09:00").await.unwrap_err().is::<NoteLockedError>());
+        assert!(manager.get_note(&note.id).await.unwrap().is_some());
+
+        manager.unlock_note(&note.id).await.unwrap();
+        let updated = manager.update_note(content_update(&note.id, "changed")).await.unwrap().unwrap();
+        assert_eq!(updated.content, "changed");
+    }
+}
@@ -1,21 +1,50 @@
-use crate::storage::FileStorageManager;
-use crate::models::{Note, NoteMetadata, CreateNoteRequest, UpdateNoteRequest};
+use crate::storage::{note_change, FileStorageManager};
+use crate::tags::{inline_tags, tag_matches, tags_for_save, RuleTags};
+use crate::vault::TagPolicy;
+use crate::models::{BulkResult, Note, NoteChange, NoteMetadata, NoteSort, CreateNoteRequest, UpdateNoteRequest};
 use anyhow::Result;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use std::collections::HashSet;
-use std::fs;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+mod activity;
+mod board;
+mod crypto;
+mod daily;
+mod drafts;
+mod history;
+mod lock;
+mod outline;
+mod pins;
+mod properties;
+mod reminders;
+mod restructure;
+pub mod stats;
+mod tasks;
+
+use activity::describe;
+pub use lock::NoteLockedError;
 
 pub struct NotesManager {
     storage: FileStorageManager,
+    // Keys of encrypted notes unlocked in this session, by note id
+    sessions: Mutex<HashMap<String, crypto::SessionKey>>,
+    // File each drafted note was last found in, so drafts don't rescan the vault
+    draft_files: Mutex<HashMap<String, String>>,
 }
 
 impl NotesManager {
     pub fn new(storage: FileStorageManager) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            sessions: Mutex::new(HashMap::new()),
+            draft_files: Mutex::new(HashMap::new()),
+        }
     }
 
-    fn metadata_to_note(&self, metadata: NoteMetadata, content: String, file_path: String) -> Note {
+    // `policy` decides whether and how the body's inline tags are read
+    fn metadata_to_note(&self, metadata: NoteMetadata, content: String, file_path: String, policy: &TagPolicy) -> Note {
         let created_at = DateTime::parse_from_rfc3339(&metadata.created)
             .map(|dt| dt.with_timezone(&Utc))
             .unwrap_or_else(|_| Utc::now());
@@ -23,6 +52,20 @@ impl NotesManager {
         let modified_at = DateTime::parse_from_rfc3339(&metadata.modified)
             .map(|dt| dt.with_timezone(&Utc))
             .unwrap_or_else(|_| Utc::now());
+        
+        // Unlocked encrypted notes read as plain text; locked ones keep the armored body
+        let (content, readable) = if metadata.encrypted {
+            match self.open_with_session(&metadata.id, &content) {
+                Some(plain) => (plain, true),
+                None => (content, false),
+            }
+        } else {
+            (content, true)
+        };
+        
+        // Counting the armor would only measure ciphertext
+        let counts = if readable { stats::count_text(&content) } else { stats::TextCounts::default() };
+        let inline_tags = if metadata.encrypted { vec![] } else { inline_tags(policy, &content) };
             
         Note {
             id: metadata.id,
@@ -33,13 +76,29 @@ impl NotesManager {
             modified_at,
             is_favorite: metadata.favorite,
             is_deleted: metadata.deleted,
+            is_archived: metadata.archived,
+            is_locked: metadata.locked,
+            is_encrypted: metadata.encrypted,
             tags: metadata.tags,
+            inline_tags,
             has_attachments: !metadata.attachments.is_empty(),
             attachments: metadata.attachments,
+            remind: metadata.remind,
+            properties: metadata.properties,
+            word_count: counts.words,
+            reading_time: stats::reading_time_minutes(&counts),
+            is_pinned: false,
         }
     }
 
     pub async fn create_note(&self, request: CreateNoteRequest) -> Result<Note> {
+        let (note, change) = self.save_new_note(request)?;
+        self.record_change("create_note", "Created", change)?;
+        Ok(note)
+    }
+    
+    // create_note without the journal entry, for operations that journal themselves
+    fn save_new_note(&self, request: CreateNoteRequest) -> Result<(Note, NoteChange)> {
         let id = Uuid::new_v4().to_string();
         let title = if request.title.trim().is_empty() {
             "Untitled".to_string()
@@ -53,20 +112,29 @@ impl NotesManager {
         let file_name = file_path_buf.file_name().unwrap().to_string_lossy().to_string();
         
         let now = Utc::now();
-        let metadata = NoteMetadata {
+        let mut metadata = NoteMetadata {
             id: id.clone(),
             title: title.clone(),
-            tags: request.tags.unwrap_or_default(),
+            tags: vec![],
             attachments: vec![],
             created: now.to_rfc3339(),
             modified: now.to_rfc3339(),
             favorite: false,
             deleted: false,
+            archived: false,
+            locked: false,
+            encrypted: false,
+            remind: None,
+            template: request.template,
+            properties: BTreeMap::new(),
         };
+        let settings = self.storage.load_vault_settings()?;
+        metadata.tags = tags_for_save(&settings, &metadata, request.tags.unwrap_or_default(), Some(&content), RuleTags::All);
         
         self.storage.save_note(&file_name, &metadata, &content)?;
         
-        Ok(self.metadata_to_note(metadata, content, file_name))
+        let change = note_change(None, Some(&(file_name.clone(), metadata.clone(), content.clone())));
+        Ok((self.metadata_to_note(metadata, content, file_name, &settings.tag_policy), change))
     }
     
     pub async fn get_note(&self, id: &str) -> Result<Option<Note>> {
@@ -75,7 +143,7 @@ impl NotesManager {
             if let Ok((metadata, content)) = self.storage.parse_note(&file_info.name) {
                 if metadata.id == id {
                     if !metadata.deleted {
-                        return Ok(Some(self.metadata_to_note(metadata, content, file_info.name)));
+                        return Ok(Some(self.metadata_to_note(metadata, content, file_info.name, &self.tag_policy()?)));
                     }
                 }
             }
@@ -84,14 +152,28 @@ impl NotesManager {
     }
 
     pub async fn get_all_notes(&self) -> Result<Vec<Note>> {
+        let mut notes = self.load_notes(|m| !m.deleted && !m.archived)?;
+        self.apply_pins(&mut notes, None)?;
+        Ok(notes)
+    }
+    
+    /// Every note outside the trash, archived ones included, newest edit first and
+    /// without pins. For lookups that must find a note however it is filed.
+    pub async fn get_live_notes(&self) -> Result<Vec<Note>> {
+        self.load_notes(|m| !m.deleted)
+    }
+    
+    // Notes whose front matter passes `filter`, newest edit first, before pins are applied
+    fn load_notes<F: Fn(&NoteMetadata) -> bool>(&self, filter: F) -> Result<Vec<Note>> {
         let files = self.storage.scan_existing_files()?;
+        let policy = self.tag_policy()?;
         let mut notes = Vec::new();
         
         for file_info in files {
             match self.storage.parse_note(&file_info.name) {
                 Ok((metadata, content)) => {
-                     if !metadata.deleted {
-                         notes.push(self.metadata_to_note(metadata, content, file_info.name));
+                     if filter(&metadata) {
+                         notes.push(self.metadata_to_note(metadata, content, file_info.name, &policy));
                      }
                 },
                 Err(e) => {
@@ -108,37 +190,51 @@ impl NotesManager {
         Ok(notes.into_iter().filter(|n| n.is_favorite).collect())
     }
     
-    pub async fn get_notes_by_tag(&self, tag_name: &str) -> Result<Vec<Note>> {
-        let notes = self.get_all_notes().await?;
-        Ok(notes.into_iter().filter(|n| n.tags.contains(&tag_name.to_string())).collect())
+    /// Notes tagged `tag_name`, and with `include_descendants` also those tagged
+    /// with a nested tag such as `tag_name/child`.
+    pub async fn get_notes_by_tag(&self, tag_name: &str, include_descendants: bool) -> Result<Vec<Note>> {
+        // Notes only carry inline tags when the tag policy counts them
+        let mut notes: Vec<Note> = self.load_notes(|m| !m.deleted && !m.archived)?
+            .into_iter()
+            .filter(|n| n.tags.iter().chain(&n.inline_tags).any(|t| tag_matches(t, tag_name, include_descendants)))
+            .collect();
+        self.apply_pins(&mut notes, Some(tag_name))?;
+        Ok(notes)
     }
     
     pub async fn get_untagged_notes(&self) -> Result<Vec<Note>> {
         let notes = self.get_all_notes().await?;
-        Ok(notes.into_iter().filter(|n| n.tags.is_empty()).collect())
+        Ok(notes.into_iter().filter(|n| n.tags.is_empty() && n.inline_tags.is_empty()).collect())
     }
     
-    pub async fn get_trash(&self) -> Result<Vec<Note>> {
-        let files = self.storage.scan_existing_files()?;
-        let mut notes = Vec::new();
-        
-        for file_info in files {
-            match self.storage.parse_note(&file_info.name) {
-                Ok((metadata, content)) => {
-                     if metadata.deleted {
-                         notes.push(self.metadata_to_note(metadata, content, file_info.name));
-                     }
-                },
-                Err(e) => {
-                    log::error!("Failed to parse note {}: {}", file_info.name, e);
-                }
-            }
-        }
-        notes.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    // Pins belong to the live lists, so the trash only follows `sort`
+    pub async fn get_trash(&self, sort: NoteSort) -> Result<Vec<Note>> {
+        let mut notes = self.load_notes(|m| m.deleted)?;
+        let manual_order = self.storage.load_vault_settings()?.order.global;
+        pins::sort_by(&mut notes, sort, &manual_order);
         Ok(notes)
     }
     
+    pub async fn get_archived_notes(&self) -> Result<Vec<Note>> {
+        self.load_notes(|m| !m.deleted && m.archived)
+    }
+    
     pub async fn update_note(&self, request: UpdateNoteRequest) -> Result<Option<Note>> {
+        self.update_and_record("update_note", "Edited", request).await
+    }
+    
+    // Applies `request` and journals it as `operation`
+    async fn update_and_record(&self, operation: &str, verb: &str, request: UpdateNoteRequest) -> Result<Option<Note>> {
+        let (note, change) = match self.apply_update(request).await? {
+            Some(updated) => updated,
+            None => return Ok(None),
+        };
+        self.record_change(operation, verb, change)?;
+        Ok(Some(note))
+    }
+    
+    // update_note without the journal entry, for operations that journal themselves
+    async fn apply_update(&self, request: UpdateNoteRequest) -> Result<Option<(Note, NoteChange)>> {
         // Find note file by ID
         let mut target_file: Option<String> = None;
         let mut target_metadata: Option<NoteMetadata> = None;
@@ -162,8 +258,31 @@ impl NotesManager {
         }
         
         if let (Some(file_name), Some(mut metadata), Some(mut content)) = (target_file, target_metadata, target_content) {
+            // Favouriting is the only change a locked note accepts
+            let edits_note = request.title.is_some() || request.content.is_some() || request.tags.is_some() || request.properties.is_some();
+            if metadata.locked && edits_note {
+                return Err(NoteLockedError::new(&request.id).into());
+            }
+            if let Some(properties) = &request.properties {
+                properties::validate_properties(properties)?;
+            }
+            let before = (file_name.clone(), metadata.clone(), content.clone());
+            
+            // Encrypted notes are edited as plain text and written back as ciphertext
+            let content_update = match request.content {
+                Some(update) if metadata.encrypted => {
+                    let sealed = self.seal_with_session(&request.id, &update)?;
+                    let previous = self.open_with_session(&request.id, &content);
+                    crypto::sync_body_attachments(&mut metadata, previous.as_deref(), &update);
+                    Some(sealed)
+                }
+                other => other,
+            };
+            
             let mut new_file_name = file_name.clone();
             
+            let title_changed = request.title.as_ref().is_some_and(|t| *t != metadata.title);
+            
             // Update metadata
             if let Some(title) = request.title {
                 if title != metadata.title {
@@ -178,7 +297,8 @@ impl NotesManager {
                 }
             }
             
-            if let Some(content_update) = request.content {
+            let content_changed = content_update.is_some();
+            if let Some(content_update) = content_update {
                 content = content_update;
             }
             
@@ -186,64 +306,176 @@ impl NotesManager {
                 metadata.favorite = fav;
             }
             
-            if let Some(tags) = request.tags {
-                metadata.tags = tags;
+            if request.tags.is_some() || content_changed || title_changed {
+                let settings = self.storage.load_vault_settings()?;
+                let body = if metadata.encrypted { None } else { Some(content.as_str()) };
+                let previous_body = if before.1.encrypted { None } else { Some(before.2.as_str()) };
+                let (tags, rule_tags) = match request.tags {
+                    Some(tags) => (tags, RuleTags::Skip),
+                    None => (metadata.tags.clone(), RuleTags::NewMatches(&before.1, previous_body)),
+                };
+                metadata.tags = tags_for_save(&settings, &metadata, tags, body, rule_tags);
+            }
+            
+            if let Some(properties) = request.properties {
+                metadata.properties = properties;
             }
             
             metadata.modified = Utc::now().to_rfc3339();
             
             self.storage.save_note(&new_file_name, &metadata, &content)?;
             
-            Ok(Some(self.metadata_to_note(metadata, content, new_file_name)))
+            let change = note_change(Some(&before), Some(&(new_file_name.clone(), metadata.clone(), content.clone())));
+            Ok(Some((self.metadata_to_note(metadata, content, new_file_name, &self.tag_policy()?), change)))
         } else {
             log::warn!("Note with id {} not found during update. Scanned {} files.", request.id, files.len());
             Ok(None)
         }
     }
     
+    pub async fn archive_note(&self, id: &str) -> Result<bool> {
+        self.set_note_archived(id, true).await
+    }
+    
+    pub async fn unarchive_note(&self, id: &str) -> Result<bool> {
+        self.set_note_archived(id, false).await
+    }
+    
+    async fn set_note_archived(&self, id: &str, archived: bool) -> Result<bool> {
+        let result = self.set_notes_archived(&[id.to_string()], archived).await?;
+        if !result.skipped_locked.is_empty() {
+            return Err(NoteLockedError::new(id).into());
+        }
+        Ok(!result.updated.is_empty())
+    }
+    
+    /// Archives or unarchives several notes. Locked notes are left alone and
+    /// listed in `skipped_locked`; ids that aren't found appear in neither list.
+    pub async fn set_notes_archived(&self, ids: &[String], archived: bool) -> Result<BulkResult> {
+        let now = Utc::now().to_rfc3339();
+        let mut result = BulkResult::default();
+        let mut changes = Vec::new();
+        for id in ids {
+            match self.change_metadata(id, |metadata| {
+                metadata.archived = archived;
+                metadata.modified = now.clone();
+            }) {
+                Ok(Some(change)) => changes.push(change),
+                Ok(None) => {}
+                Err(e) if e.is::<NoteLockedError>() => result.skipped_locked.push(id.clone()),
+                Err(e) => return Err(e),
+            }
+        }
+        
+        result.updated = changes.iter().map(|c| c.note_id.clone()).collect();
+        let (operation, verb) = if archived { ("archive_notes", "Archived") } else { ("unarchive_notes", "Unarchived") };
+        self.storage.record_operation(operation, describe(verb, &changes), changes)?;
+        Ok(result)
+    }
+    
     pub async fn delete_note(&self, id: &str) -> Result<bool> {
-        self.set_note_deleted(id, true).await
+        let change = self.set_note_deleted(id, true)?;
+        let found = change.is_some();
+        self.storage.record_operation("delete_note", describe("Moved to trash", change.as_slice()), change.into_iter().collect())?;
+        Ok(found)
     }
     
     pub async fn restore_note(&self, id: &str) -> Result<bool> {
-        self.set_note_deleted(id, false).await
+        let change = self.set_note_deleted(id, false)?;
+        let found = change.is_some();
+        self.storage.record_operation("restore_note", describe("Restored", change.as_slice()), change.into_iter().collect())?;
+        Ok(found)
     }
     
-    async fn set_note_deleted(&self, id: &str, deleted: bool) -> Result<bool> {
+    fn set_note_deleted(&self, id: &str, deleted: bool) -> Result<Option<NoteChange>> {
+        let now = Utc::now().to_rfc3339();
+        self.change_metadata(id, |metadata| {
+            metadata.deleted = deleted;
+            metadata.modified = now;
+        })
+    }
+    
+    // Applies `update` to a note's front matter and saves it, leaving `modified` as is.
+    // Locked notes are refused.
+    fn update_metadata<F: FnOnce(&mut NoteMetadata)>(&self, id: &str, update: F) -> Result<Option<Note>> {
         let files = self.storage.scan_existing_files()?;
         for file_info in files {
             if let Ok((mut metadata, content)) = self.storage.parse_note(&file_info.name) {
                 if metadata.id == id {
-                    metadata.deleted = deleted;
-                    metadata.modified = Utc::now().to_rfc3339();
+                    if metadata.locked {
+                        return Err(NoteLockedError::new(id).into());
+                    }
+                    update(&mut metadata);
                     self.storage.save_note(&file_info.name, &metadata, &content)?;
-                    return Ok(true);
+                    return Ok(Some(self.metadata_to_note(metadata, content, file_info.name, &self.tag_policy()?)));
                 }
             }
         }
-        Ok(false)
+        Ok(None)
+    }
+    
+    // Like update_metadata, but returns the change for the activity journal
+    fn change_metadata<F: FnOnce(&mut NoteMetadata)>(&self, id: &str, update: F) -> Result<Option<NoteChange>> {
+        let before = match self.storage.find_note_file(id)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        let (file_name, mut metadata, content) = before.clone();
+        if metadata.locked {
+            return Err(NoteLockedError::new(id).into());
+        }
+        update(&mut metadata);
+        self.storage.save_note(&file_name, &metadata, &content)?;
+        Ok(Some(note_change(Some(&before), Some(&(file_name, metadata, content)))))
     }
     
     pub async fn permanently_delete_note(&self, id: &str) -> Result<bool> {
-        let files = self.storage.scan_existing_files()?;
-        for file_info in files {
-            if let Ok((metadata, _)) = self.storage.parse_note(&file_info.name) {
-                if metadata.id == id {
-                    self.storage.delete_note_file(&file_info.name)?;
-                    return Ok(true);
-                }
-            }
+        let before = match self.storage.find_note_file(id)? {
+            Some(found) => found,
+            None => return Ok(false),
+        };
+        if before.1.locked {
+            return Err(NoteLockedError::new(id).into());
         }
-        Ok(false)
+        self.storage.delete_note_file(&before.0)?;
+        self.storage.delete_history(id)?;
+        self.forget_pins(id)?;
+        
+        let change = note_change(Some(&before), None);
+        self.storage.record_operation("permanently_delete_note", format!("Deleted '{}' permanently", change.title), vec![change])?;
+        Ok(true)
+    }
+    
+    fn tag_policy(&self) -> Result<TagPolicy> {
+        Ok(self.storage.load_vault_settings()?.tag_policy)
     }
     
-    pub async fn search_notes(&self, query: &str, tag_filter: Option<&str>) -> Result<Vec<Note>> {
-        let base_notes = match tag_filter {
-            Some("All Notes") | None => self.get_all_notes().await?,
-            Some("Favorites") => self.get_favorite_notes().await?,
-            Some("Untagged") => self.get_untagged_notes().await?,
-            Some("Trash") => self.get_trash().await?,
-            Some(tag_name) => self.get_notes_by_tag(tag_name).await?,
+    /// Notes in the `tag_filter` view whose title or body contains `query`.
+    /// Without a `sort`, title matches rank above body matches.
+    pub async fn search_notes(&self, query: &str, tag_filter: Option<&str>, sort: Option<NoteSort>) -> Result<Vec<Note>> {
+        // `in:archive` anywhere in the query searches archived notes instead
+        let in_archive = query.split_whitespace().any(|term| term.eq_ignore_ascii_case("in:archive"));
+        let query = if in_archive {
+            query.split_whitespace().filter(|term| !term.eq_ignore_ascii_case("in:archive")).collect::<Vec<_>>().join(" ")
+        } else {
+            query.to_string()
+        };
+        
+        let base_notes = if in_archive {
+            let archived = self.get_archived_notes().await?;
+            match tag_filter {
+                Some("All Notes") | Some("Archive") | None => archived,
+                Some(tag_name) => archived.into_iter().filter(|n| n.tags.iter().any(|t| t == tag_name)).collect(),
+            }
+        } else {
+            match tag_filter {
+                Some("All Notes") | None => self.get_all_notes().await?,
+                Some("Favorites") => self.get_favorite_notes().await?,
+                Some("Untagged") => self.get_untagged_notes().await?,
+                Some("Trash") => self.get_trash(NoteSort::Modified).await?,
+                Some("Archive") => self.get_archived_notes().await?,
+                Some(tag_name) => self.get_notes_by_tag(tag_name, false).await?,
+            }
         };
         
         let query_lower = query.to_lowercase();
@@ -251,17 +483,32 @@ impl NotesManager {
         
         for note in base_notes {
             let title_matches = note.title.to_lowercase().contains(&query_lower);
-            let content_matches = note.content.to_lowercase().contains(&query_lower);
+            // Encrypted bodies are never searched, even while unlocked
+            let content_matches = !note.is_encrypted && note.content.to_lowercase().contains(&query_lower);
             
             if title_matches || content_matches {
                 matching_notes.push(note);
             }
         }
         
+        if let Some(sort) = sort {
+            match tag_filter {
+                Some("Trash") => self.storage.load_vault_settings().map(|s| pins::sort_by(&mut matching_notes, sort, &s.order.global))?,
+                Some("All Notes") | Some("Favorites") | Some("Untagged") | Some("Archive") | None => self.sort_notes(&mut matching_notes, sort, None)?,
+                Some(tag_name) => self.sort_notes(&mut matching_notes, sort, Some(tag_name))?,
+            }
+            return Ok(matching_notes);
+        }
+        
         matching_notes.sort_by(|a, b| {
             let a_title_match = a.title.to_lowercase().contains(&query_lower);
             let b_title_match = b.title.to_lowercase().contains(&query_lower);
             
+            // Pins from the base list stay on top and keep their order
+            if a.is_pinned || b.is_pinned {
+                return b.is_pinned.cmp(&a.is_pinned);
+            }
+            
             match (a_title_match, b_title_match) {
                 (true, false) => std::cmp::Ordering::Less,
                 (false, true) => std::cmp::Ordering::Greater,
@@ -282,8 +529,11 @@ impl NotesManager {
              if let Ok((mut metadata, content)) = self.storage.parse_note(&file_info.name) {
                 if metadata.id == note_id {
                     if !metadata.attachments.contains(&attachment_path.to_string()) {
+                        let before = (file_info.name.clone(), metadata.clone(), content.clone());
                         metadata.attachments.push(attachment_path.to_string());
                         self.storage.save_note(&file_info.name, &metadata, &content)?;
+                        let change = note_change(Some(&before), Some(&(file_info.name, metadata, content)));
+                        self.record_change("add_attachment", "Attached a file to", change)?;
                     }
                     return Ok(());
                 }
@@ -297,9 +547,15 @@ impl NotesManager {
         for file_info in files {
              if let Ok((mut metadata, content)) = self.storage.parse_note(&file_info.name) {
                 if metadata.id == note_id {
+                    if metadata.locked {
+                        return Err(NoteLockedError::new(note_id).into());
+                    }
                     if let Some(pos) = metadata.attachments.iter().position(|x| x == attachment_path) {
+                        let before = (file_info.name.clone(), metadata.clone(), content.clone());
                         metadata.attachments.remove(pos);
                         self.storage.save_note(&file_info.name, &metadata, &content)?;
+                        let change = note_change(Some(&before), Some(&(file_info.name, metadata, content)));
+                        self.record_change("remove_attachment", "Removed a file from", change)?;
                     }
                     return Ok(());
                 }
@@ -307,40 +563,31 @@ impl NotesManager {
         }
         Err(anyhow::anyhow!("Note not found"))
     }
+}
 
-    pub async fn cleanup_unused_attachments(&self) -> Result<usize> {
-        let files = self.storage.scan_existing_files()?;
-        let mut used_attachments = HashSet::new();
-        
-        for file_info in files {
-            if let Ok((metadata, _)) = self.storage.parse_note(&file_info.name) {
-                for att in metadata.attachments {
-                    used_attachments.insert(att);
-                }
-            }
-        }
-        
-        let attachments_dir = self.storage.notes_directory.join("attachments");
-        if !attachments_dir.exists() {
-            return Ok(0);
-        }
-        
-        let mut deleted_count = 0;
-        let entries = fs::read_dir(&attachments_dir)?;
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(name) = path.file_name() {
-                    let relative_path = format!("attachments/{}", name.to_string_lossy());
-                    if !used_attachments.contains(&relative_path) {
-                        fs::remove_file(&path)?;
-                        deleted_count += 1;
-                    }
-                }
-            }
-        }
-        
-        Ok(deleted_count)
+#[cfg(test)]
+mod tests {
+    use crate::test_support::test_vault;
+
+    #[tokio::test]
+    async fn test_archived_notes_hidden_but_searchable() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let note = vault.create_note("Old project", Some("retired plans"), &["work"]).await;
+
+        let result = manager.set_notes_archived(&[note.id.clone(), "missing".to_string()], true).await.unwrap();
+        assert_eq!(result.updated, vec![note.id.clone()]);
+        assert!(manager.get_all_notes().await.unwrap().is_empty());
+        assert!(manager.get_notes_by_tag("work", false).await.unwrap().is_empty());
+        assert!(manager.search_notes("retired", None, None).await.unwrap().is_empty());
+
+        let found = manager.search_notes("retired in:archive", Some("work"), None).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].is_archived);
+
+        manager.unarchive_note(&note.id).await.unwrap();
+        assert_eq!(manager.get_all_notes().await.unwrap().len(), 1);
+        assert!(manager.get_archived_notes().await.unwrap().is_empty());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,299 @@
+use super::NotesManager;
+use crate::models::{Note, NoteSection, OutlineHeading, UpdateNoteRequest};
+use anyhow::Result;
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag};
+use std::collections::HashMap;
+
+// Flat heading with the byte range of its whole section
+#[derive(Debug, Clone)]
+pub(super) struct HeadingSpan {
+    pub level: u8,
+    pub text: String,
+    pub slug: String,
+    pub start: usize,
+    pub heading_end: usize,
+    pub section_end: usize,
+}
+
+impl NotesManager {
+    pub async fn get_note_outline(&self, note_id: &str) -> Result<Option<Vec<OutlineHeading>>> {
+        Ok(self.get_note(note_id).await?.map(|note| build_outline(&note.content)))
+    }
+
+    /// Reads the section under `heading`, matched by slug or (case-insensitive) heading text.
+    pub async fn get_note_section(&self, note_id: &str, heading: &str) -> Result<Option<NoteSection>> {
+        let note = match self.get_note(note_id).await? {
+            Some(note) => note,
+            None => return Ok(None),
+        };
+        Ok(find_heading(&note.content, heading).map(|span| section_from_span(&note.content, &span)))
+    }
+
+    /// Replaces the body under `heading`, keeping the heading line itself.
+    pub async fn replace_note_section(&self, note_id: &str, heading: &str, body: &str) -> Result<Option<Note>> {
+        let note = match self.get_note(note_id).await? {
+            Some(note) => note,
+            None => return Ok(None),
+        };
+        let span = find_heading(&note.content, heading)
+            .ok_or_else(|| anyhow::anyhow!("Heading '{}' not found in note {}", heading, note_id))?;
+
+        let mut body = body.to_string();
+        if span.section_end < note.content.len() && !body.is_empty() && !body.ends_with('\n') {
+            body.push('\n');
+        }
+
+        let content = format!("{}{}{}", &note.content[..span.heading_end], body, &note.content[span.section_end..]);
+        self.update_and_record("replace_note_section", "Rewrote a section of", content_update(note_id, content)).await
+    }
+
+    /// Cuts the section under `heading` out of one note and appends it to another.
+    pub async fn move_note_section(&self, note_id: &str, heading: &str, target_note_id: &str) -> Result<Option<Note>> {
+        if note_id == target_note_id {
+            return Err(anyhow::anyhow!("Source and target note are the same"));
+        }
+
+        let (source, target) = match (self.get_note(note_id).await?, self.get_note(target_note_id).await?) {
+            (Some(source), Some(target)) => (source, target),
+            _ => return Ok(None),
+        };
+        Self::ensure_unlocked(&source)?;
+        Self::ensure_unlocked(&target)?;
+        Self::ensure_not_encrypted(&source)?;
+        Self::ensure_not_encrypted(&target)?;
+        let span = find_heading(&source.content, heading)
+            .ok_or_else(|| anyhow::anyhow!("Heading '{}' not found in note {}", heading, note_id))?;
+
+        let section = source.content[span.start..span.section_end].trim_end().to_string();
+        let remaining = format!("{}{}", &source.content[..span.start], &source.content[span.section_end..]);
+
+        let mut target_content = target.content.trim_end().to_string();
+        if !target_content.is_empty() {
+            target_content.push_str("\n\n");
+        }
+        target_content.push_str(&section);
+        target_content.push('\n');
+
+        // Write the target first so a failure never loses the section
+        let (updated_target, target_change) = match self.apply_update(content_update(target_note_id, target_content)).await? {
+            Some(updated) => updated,
+            None => return Ok(None),
+        };
+        let mut changes = vec![target_change];
+        changes.extend(self.apply_update(content_update(note_id, remaining)).await?.map(|(_, change)| change));
+        let summary = format!("Moved '{}' from '{}' to '{}'", heading, source.title, target.title);
+        self.storage.record_operation("move_note_section", summary, changes)?;
+        Ok(Some(updated_target))
+    }
+}
+
+pub(super) fn content_update(note_id: &str, content: String) -> UpdateNoteRequest {
+    UpdateNoteRequest {
+        id: note_id.to_string(),
+        title: None,
+        content: Some(content),
+        is_favorite: None,
+        tags: None,
+        properties: None,
+    }
+}
+
+fn heading_level(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+pub(super) fn parse_headings(content: &str) -> Vec<HeadingSpan> {
+    let mut spans: Vec<HeadingSpan> = Vec::new();
+    let mut current: Option<HeadingSpan> = None;
+    let mut slug_counts: HashMap<String, usize> = HashMap::new();
+
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS | Options::ENABLE_HEADING_ATTRIBUTES;
+    for (event, range) in Parser::new_ext(content, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading(level, _, _)) => {
+                current = Some(HeadingSpan {
+                    level: heading_level(level),
+                    text: String::new(),
+                    slug: String::new(),
+                    start: range.start,
+                    heading_end: range.end,
+                    section_end: content.len(),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = current.as_mut() {
+                    heading.text.push_str(&text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some(mut heading) = current.take() {
+                    heading.text = heading.text.trim().to_string();
+                    heading.slug = unique_slug(&heading.text, &mut slug_counts);
+                    // Section bodies start on the line after the heading
+                    if content[heading.heading_end..].starts_with('\n') {
+                        heading.heading_end += 1;
+                    } else if content[heading.heading_end..].starts_with("\r\n") {
+                        heading.heading_end += 2;
+                    }
+                    spans.push(heading);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // A section runs until the next heading of the same or a higher level
+    for i in 0..spans.len() {
+        if let Some(next) = spans[i + 1..].iter().find(|s| s.level <= spans[i].level) {
+            spans[i].section_end = next.start;
+        }
+    }
+
+    spans
+}
+
+/// GitHub-style anchor: lowercase, punctuation dropped, spaces to hyphens, duplicates suffixed.
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                Some(c)
+            } else if c.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn unique_slug(text: &str, counts: &mut HashMap<String, usize>) -> String {
+    let base = slugify(text);
+    let count = counts.entry(base.clone()).or_insert(0);
+    let slug = if *count == 0 { base } else { format!("{}-{}", base, count) };
+    *count += 1;
+    slug
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+fn build_outline(content: &str) -> Vec<OutlineHeading> {
+    let spans = parse_headings(content);
+    let mut roots: Vec<OutlineHeading> = Vec::new();
+    // Path of indices from the root to the most recent heading
+    let mut path: Vec<usize> = Vec::new();
+
+    for span in spans {
+        let node = OutlineHeading {
+            level: span.level,
+            text: span.text.clone(),
+            slug: span.slug.clone(),
+            byte_start: span.start,
+            byte_end: span.section_end,
+            line_start: line_of(content, span.start),
+            line_end: line_of(content, span.section_end.saturating_sub(1).max(span.start)),
+            children: vec![],
+        };
+
+        // Walk up until the parent has a lower level than this heading
+        let mut depth = path.len();
+        while depth > 0 && outline_node(&roots, &path[..depth]).level >= node.level {
+            depth -= 1;
+        }
+        path.truncate(depth);
+
+        let siblings = if depth == 0 { &mut roots } else { &mut outline_node_mut(&mut roots, &path).children };
+        siblings.push(node);
+        path.push(siblings.len() - 1);
+    }
+
+    roots
+}
+
+fn outline_node<'a>(roots: &'a [OutlineHeading], path: &[usize]) -> &'a OutlineHeading {
+    let mut node = &roots[path[0]];
+    for &i in &path[1..] {
+        node = &node.children[i];
+    }
+    node
+}
+
+fn outline_node_mut<'a>(roots: &'a mut [OutlineHeading], path: &[usize]) -> &'a mut OutlineHeading {
+    let mut node = &mut roots[path[0]];
+    for &i in &path[1..] {
+        node = &mut node.children[i];
+    }
+    node
+}
+
+fn find_heading(content: &str, heading: &str) -> Option<HeadingSpan> {
+    let heading = heading.trim().trim_start_matches('#');
+    let spans = parse_headings(content);
+    let slug = slugify(heading);
+    spans.iter()
+        .find(|s| s.slug == heading || s.text.eq_ignore_ascii_case(heading))
+        .or_else(|| spans.iter().find(|s| s.slug == slug))
+        .cloned()
+}
+
+fn section_from_span(content: &str, span: &HeadingSpan) -> NoteSection {
+    NoteSection {
+        level: span.level,
+        heading: span.text.clone(),
+        slug: span.slug.clone(),
+        content: content[span.heading_end.min(span.section_end)..span.section_end].to_string(),
+        line_start: line_of(content, span.start),
+        line_end: line_of(content, span.section_end.saturating_sub(1).max(span.start)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "# Title\nintro\n## Setup\nsteps\n```\n# not a heading\n```\n### Details\nmore\n## Usage\nuse it\n## Setup\nagain\n";
+
+    #[test]
+    fn test_outline_ignores_code_fences_and_nests() {
+        let outline = build_outline(DOC);
+        assert_eq!(outline.len(), 1);
+
+        let title = &outline[0];
+        assert_eq!(title.slug, "title");
+        assert_eq!(title.line_start, 1);
+        assert_eq!(title.children.len(), 3);
+        assert_eq!(title.children[0].text, "Setup");
+        assert_eq!(title.children[0].line_end, 9);
+        assert_eq!(title.children[0].children[0].slug, "details");
+        assert_eq!(title.children[2].slug, "setup-1");
+    }
+
+    #[test]
+    fn test_find_section() {
+        let span = find_heading(DOC, "#Setup").unwrap();
+        let section = section_from_span(DOC, &span);
+        assert_eq!(section.content, "steps\n```\n# not a heading\n```\n### Details\nmore\n");
+
+        let span = find_heading(DOC, "setup-1").unwrap();
+        assert_eq!(section_from_span(DOC, &span).content, "again\n");
+        assert!(find_heading(DOC, "missing").is_none());
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("API `v2` notes"), "api-v2-notes");
+        assert_eq!(slugify("会议 记录"), "会议-记录");
+    }
+}
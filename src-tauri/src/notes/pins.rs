@@ -0,0 +1,208 @@
+use super::NotesManager;
+use crate::models::{Note, NoteSort};
+use anyhow::Result;
+use std::cmp::Reverse;
+
+impl NotesManager {
+    /// Pins a note globally (`tag == None`) or within a tag's list, appending it after existing pins.
+    pub async fn pin_note(&self, note_id: &str, tag: Option<&str>) -> Result<bool> {
+        let note = match self.get_note(note_id).await? {
+            Some(note) => note,
+            None => return Ok(false),
+        };
+        if let Some(tag) = tag {
+            if !note.tags.iter().any(|t| t == tag) {
+                return Err(anyhow::anyhow!("Note {} is not tagged '{}'", note_id, tag));
+            }
+        }
+
+        let mut settings = self.storage.load_vault_settings()?;
+        let pinned = settings.pinned_mut(tag);
+        if !pinned.iter().any(|id| id == note_id) {
+            pinned.push(note_id.to_string());
+            self.storage.save_vault_settings(&settings)?;
+        }
+        Ok(true)
+    }
+
+    pub async fn unpin_note(&self, note_id: &str, tag: Option<&str>) -> Result<bool> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let pinned = settings.pinned_mut(tag);
+        let before = pinned.len();
+        pinned.retain(|id| id != note_id);
+        let removed = pinned.len() != before;
+
+        if let Some(tag) = tag {
+            if settings.pinned(Some(tag)).is_empty() {
+                settings.pins.tags.remove(tag);
+            }
+        }
+        if removed {
+            self.storage.save_vault_settings(&settings)?;
+        }
+        Ok(removed)
+    }
+
+    /// Reorders pins to follow `note_ids`; pins left out keep their relative order at the end.
+    pub async fn reorder_pinned_notes(&self, note_ids: &[String], tag: Option<&str>) -> Result<Vec<String>> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let current = settings.pinned(tag).to_vec();
+
+        let mut ordered: Vec<String> = Vec::new();
+        for id in note_ids {
+            if current.contains(id) && !ordered.contains(id) {
+                ordered.push(id.clone());
+            }
+        }
+        for id in current {
+            if !ordered.contains(&id) {
+                ordered.push(id);
+            }
+        }
+
+        *settings.pinned_mut(tag) = ordered.clone();
+        self.storage.save_vault_settings(&settings)?;
+        Ok(ordered)
+    }
+
+    /// Stores the order used by `NoteSort::Manual` for the main list (`tag == None`) or a tag's list.
+    pub async fn set_manual_order(&self, note_ids: &[String], tag: Option<&str>) -> Result<Vec<String>> {
+        let mut ordered: Vec<String> = Vec::new();
+        for id in note_ids {
+            if !ordered.contains(id) {
+                ordered.push(id.clone());
+            }
+        }
+        let mut settings = self.storage.load_vault_settings()?;
+        *settings.order.list_mut(tag) = ordered.clone();
+        if ordered.is_empty() {
+            if let Some(tag) = tag {
+                settings.order.tags.remove(tag);
+            }
+        }
+        self.storage.save_vault_settings(&settings)?;
+        Ok(ordered)
+    }
+
+    /// Re-sorts a list by `sort`, then moves the pins of `tag`'s list (the
+    /// main list when None) to the front.
+    pub fn sort_notes(&self, notes: &mut [Note], sort: NoteSort, tag: Option<&str>) -> Result<()> {
+        let settings = self.storage.load_vault_settings()?;
+        sort_by(notes, sort, settings.order.list(tag));
+        self.apply_pins(notes, tag)
+    }
+
+    pub async fn get_pinned_note_ids(&self, tag: Option<&str>) -> Result<Vec<String>> {
+        Ok(self.storage.load_vault_settings()?.pinned(tag).to_vec())
+    }
+
+    // Moves pinned notes to the front in pin order; everything else keeps its sort
+    pub(super) fn apply_pins(&self, notes: &mut [Note], tag: Option<&str>) -> Result<()> {
+        let settings = self.storage.load_vault_settings()?;
+        let pinned = settings.pinned(tag);
+        if pinned.is_empty() {
+            return Ok(());
+        }
+
+        for note in notes.iter_mut() {
+            note.is_pinned = pinned.contains(&note.id);
+        }
+        notes.sort_by_key(|n| pinned.iter().position(|id| *id == n.id).unwrap_or(pinned.len()));
+        Ok(())
+    }
+
+    // Drops a permanently deleted note from every pin list and manual order
+    pub(super) fn forget_pins(&self, note_id: &str) -> Result<()> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let pins_changed = settings.pins.forget(note_id);
+        let order_changed = settings.order.forget(note_id);
+
+        if pins_changed || order_changed {
+            self.storage.save_vault_settings(&settings)?;
+        }
+        Ok(())
+    }
+}
+
+// Stable, so ties keep the newest-edit-first order lists are loaded in
+pub(super) fn sort_by(notes: &mut [Note], sort: NoteSort, manual_order: &[String]) {
+    match sort {
+        NoteSort::Modified => notes.sort_by_key(|n| Reverse(n.modified_at)),
+        NoteSort::Created => notes.sort_by_key(|n| Reverse(n.created_at)),
+        NoteSort::Title => notes.sort_by_key(|n| n.title.to_lowercase()),
+        NoteSort::Manual => notes.sort_by_key(|n| manual_order.iter().position(|id| *id == n.id).unwrap_or(manual_order.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+
+    fn titles(notes: &[Note]) -> Vec<&str> {
+        notes.iter().map(|n| n.title.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_pins_sort_before_modified() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let mut ids = Vec::new();
+        for title in ["A", "B", "C"] {
+            let note = vault.create_note(title, Some(""), &["work"]).await;
+            ids.push(note.id);
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        assert_eq!(titles(&manager.get_all_notes().await.unwrap()), vec!["C", "B", "A"]);
+
+        manager.pin_note(&ids[0], None).await.unwrap();
+        manager.pin_note(&ids[1], None).await.unwrap();
+        let notes = manager.get_all_notes().await.unwrap();
+        assert_eq!(titles(&notes), vec!["A", "B", "C"]);
+        assert!(notes[0].is_pinned && !notes[2].is_pinned);
+
+        manager.reorder_pinned_notes(&[ids[1].clone()], None).await.unwrap();
+        assert_eq!(titles(&manager.get_all_notes().await.unwrap()), vec!["B", "A", "C"]);
+
+        // Tag pins are independent of the global list
+        manager.pin_note(&ids[2], Some("work")).await.unwrap();
+        assert_eq!(titles(&manager.get_notes_by_tag("work", false).await.unwrap()), vec!["C", "B", "A"]);
+        assert_eq!(titles(&manager.search_notes("", Some("work"), None).await.unwrap())[0], "C");
+
+        assert!(manager.unpin_note(&ids[1], None).await.unwrap());
+        manager.permanently_delete_note(&ids[0]).await.unwrap();
+        assert!(manager.get_pinned_note_ids(None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_manual_order_and_unpinned_trash() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let mut ids = Vec::new();
+        for title in ["b", "C", "a"] {
+            let note = vault.create_note(title, Some(""), &[]).await;
+            ids.push(note.id);
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        // Unlisted notes keep their modified order after the ordered ones
+        manager.set_manual_order(&[ids[1].clone(), ids[0].clone(), ids[1].clone()], None).await.unwrap();
+        let mut notes = manager.get_all_notes().await.unwrap();
+        manager.sort_notes(&mut notes, NoteSort::Manual, None).unwrap();
+        assert_eq!(titles(&notes), vec!["C", "b", "a"]);
+        manager.sort_notes(&mut notes, NoteSort::Title, None).unwrap();
+        assert_eq!(titles(&notes), vec!["a", "b", "C"]);
+
+        manager.pin_note(&ids[0], None).await.unwrap();
+        manager.sort_notes(&mut notes, NoteSort::Created, None).unwrap();
+        assert_eq!(titles(&notes), vec!["b", "a", "C"]);
+
+        for id in &ids {
+            manager.delete_note(id).await.unwrap();
+        }
+        assert_eq!(titles(&manager.get_trash(NoteSort::Modified).await.unwrap()), vec!["a", "C", "b"]);
+        assert_eq!(titles(&manager.get_trash(NoteSort::Manual).await.unwrap()), vec!["C", "b", "a"]);
+    }
+}
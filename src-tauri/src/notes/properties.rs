@@ -0,0 +1,339 @@
+use super::NotesManager;
+use crate::models::{Note, NoteMetadata, PropertyFilter, PropertyOperator, PropertyQuery, PropertySummary, PropertyType, UpdateNoteRequest};
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde_yaml::Value;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::OnceLock;
+
+// Cap on distinct values reported per key by get_vault_properties
+const MAX_SUMMARY_VALUES: usize = 50;
+
+#[derive(Default)]
+struct PropertyStats {
+    types: BTreeMap<PropertyType, usize>,
+    note_count: usize,
+    values: Vec<Value>,
+}
+
+impl NotesManager {
+    pub async fn set_note_property(&self, note_id: &str, key: &str, value: Value) -> Result<Option<Note>> {
+        let key = validate_property_key(key)?;
+        let note = match self.get_note(note_id).await? {
+            Some(note) => note,
+            None => return Ok(None),
+        };
+
+        let mut properties = note.properties;
+        properties.insert(key, coerce_property_value(value));
+        self.update_properties(note_id, properties).await
+    }
+
+    pub async fn unset_note_property(&self, note_id: &str, key: &str) -> Result<Option<Note>> {
+        let note = match self.get_note(note_id).await? {
+            Some(note) => note,
+            None => return Ok(None),
+        };
+
+        if !note.properties.contains_key(key) {
+            return Ok(Some(note));
+        }
+        let mut properties = note.properties;
+        properties.remove(key);
+        self.update_properties(note_id, properties).await
+    }
+
+    /// Lists every property key used outside the trash, archived notes included,
+    /// with its inferred type and distinct values.
+    pub async fn get_vault_properties(&self) -> Result<Vec<PropertySummary>> {
+        let notes = self.get_live_notes().await?;
+        let mut summaries: BTreeMap<String, PropertyStats> = BTreeMap::new();
+
+        for note in notes {
+            for (key, value) in note.properties {
+                let stats = summaries.entry(key).or_default();
+                *stats.types.entry(infer_property_type(&value)).or_insert(0) += 1;
+                stats.note_count += 1;
+
+                let items = match value {
+                    Value::Sequence(items) => items,
+                    other => vec![other],
+                };
+                for item in items {
+                    if stats.values.len() < MAX_SUMMARY_VALUES && !stats.values.contains(&item) {
+                        stats.values.push(item);
+                    }
+                }
+            }
+        }
+
+        Ok(summaries.into_iter().map(|(key, mut stats)| {
+            // Mixed keys report whichever type most notes use
+            let property_type = stats.types.into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1))
+                .map(|(t, _)| t)
+                .unwrap_or(PropertyType::Text);
+            stats.values.sort_by(|a, b| compare_values(a, b).unwrap_or(Ordering::Equal));
+
+            PropertySummary { key, property_type, note_count: stats.note_count, values: stats.values }
+        }).collect())
+    }
+
+    pub async fn query_notes_by_property(&self, query: &PropertyQuery) -> Result<Vec<Note>> {
+        let notes = if query.include_archived { self.get_live_notes().await? } else { self.get_all_notes().await? };
+        let mut matching: Vec<Note> = notes.into_iter()
+            .filter(|note| query.filters.iter().all(|f| matches_filter(&note.properties, f)))
+            .collect();
+
+        if let Some(sort_key) = &query.sort_by {
+            // Notes without the property always sort last; get_all_notes order breaks ties
+            matching.sort_by(|a, b| match (a.properties.get(sort_key), b.properties.get(sort_key)) {
+                (Some(x), Some(y)) => {
+                    let ordering = compare_values(x, y).unwrap_or(Ordering::Equal);
+                    if query.descending { ordering.reverse() } else { ordering }
+                }
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            });
+        }
+
+        Ok(matching)
+    }
+
+    async fn update_properties(&self, note_id: &str, properties: BTreeMap<String, Value>) -> Result<Option<Note>> {
+        self.update_and_record("update_properties", "Changed properties of", UpdateNoteRequest {
+            id: note_id.to_string(),
+            title: None,
+            content: None,
+            is_favorite: None,
+            tags: None,
+            properties: Some(properties),
+        }).await
+    }
+}
+
+/// Rejects property maps that would write a NoteMetadata field a second time.
+pub(super) fn validate_properties(properties: &BTreeMap<String, Value>) -> Result<()> {
+    for key in properties.keys() {
+        if validate_property_key(key)? != *key {
+            return Err(anyhow::anyhow!("Property name '{}' has surrounding whitespace", key));
+        }
+    }
+    Ok(())
+}
+
+fn validate_property_key(key: &str) -> Result<String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(anyhow::anyhow!("Property name cannot be empty"));
+    }
+    if reserved_keys().contains(key) {
+        return Err(anyhow::anyhow!("'{}' is a reserved note field and cannot be used as a property", key));
+    }
+    Ok(key.to_string())
+}
+
+// Front matter keys owned by NoteMetadata, which can't be used as custom
+// properties. Read off a metadata value with every optional field set, so new
+// fields are reserved without keeping a list by hand.
+fn reserved_keys() -> &'static HashSet<String> {
+    static KEYS: OnceLock<HashSet<String>> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let metadata = NoteMetadata {
+            id: String::new(),
+            title: String::new(),
+            tags: vec![],
+            attachments: vec![],
+            created: String::new(),
+            modified: String::new(),
+            favorite: true,
+            deleted: true,
+            archived: true,
+            locked: true,
+            encrypted: true,
+            remind: Some(String::new()),
+            template: Some(String::new()),
+            properties: BTreeMap::new(),
+        };
+        match serde_yaml::to_value(&metadata) {
+            Ok(Value::Mapping(mapping)) => mapping.keys().filter_map(|k| k.as_str().map(String::from)).collect(),
+            _ => HashSet::new(),
+        }
+    })
+}
+
+/// Infers the property type from a front matter value.
+pub fn infer_property_type(value: &Value) -> PropertyType {
+    match value {
+        Value::Bool(_) => PropertyType::Boolean,
+        Value::Number(_) => PropertyType::Number,
+        Value::Sequence(_) => PropertyType::List,
+        Value::String(s) => {
+            let s = s.trim();
+            if NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok() {
+                PropertyType::Date
+            } else if parse_datetime(s).is_some() {
+                PropertyType::DateTime
+            } else if s.starts_with("http://") || s.starts_with("https://") {
+                PropertyType::Url
+            } else {
+                PropertyType::Text
+            }
+        }
+        _ => PropertyType::Text,
+    }
+}
+
+fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.naive_utc());
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+}
+
+// Values from the UI arrive as strings; store numbers and booleans as real YAML scalars
+fn coerce_property_value(value: Value) -> Value {
+    match value {
+        Value::String(s) => {
+            let trimmed = s.trim();
+            if trimmed == "true" || trimmed == "false" {
+                Value::Bool(trimmed == "true")
+            } else if let Ok(i) = trimmed.parse::<i64>() {
+                Value::Number(i.into())
+            } else if let Ok(f) = trimmed.parse::<f64>() {
+                if f.is_finite() { Value::Number(f.into()) } else { Value::String(s) }
+            } else {
+                Value::String(s)
+            }
+        }
+        Value::Sequence(items) => Value::Sequence(items.into_iter().map(coerce_property_value).collect()),
+        other => other,
+    }
+}
+
+fn compare_values(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64()?.partial_cmp(&y.as_f64()?),
+        (Value::Bool(x), Value::Bool(y)) => Some(x.cmp(y)),
+        (Value::String(x), Value::String(y)) => {
+            match (parse_datetime_or_date(x), parse_datetime_or_date(y)) {
+                (Some(dx), Some(dy)) => Some(dx.cmp(&dy)),
+                _ => Some(x.to_lowercase().cmp(&y.to_lowercase())),
+            }
+        }
+        _ => Some(value_to_string(a).to_lowercase().cmp(&value_to_string(b).to_lowercase())),
+    }
+}
+
+fn parse_datetime_or_date(s: &str) -> Option<NaiveDateTime> {
+    parse_datetime(s).or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim().to_string(),
+    }
+}
+
+fn matches_filter(properties: &BTreeMap<String, Value>, filter: &PropertyFilter) -> bool {
+    let actual = properties.get(&filter.key);
+    let expected = filter.value.clone().map(coerce_property_value);
+
+    match (filter.op, actual, expected) {
+        (PropertyOperator::Exists, actual, _) => actual.is_some(),
+        (PropertyOperator::NotExists, actual, _) => actual.is_none(),
+        (PropertyOperator::Ne, None, _) => true,
+        (_, None, _) | (_, _, None) => false,
+        (PropertyOperator::Contains, Some(Value::Sequence(items)), Some(expected)) => {
+            items.iter().any(|item| compare_values(item, &expected) == Some(Ordering::Equal))
+        }
+        (PropertyOperator::Contains, Some(actual), Some(expected)) => {
+            value_to_string(actual).to_lowercase().contains(&value_to_string(&expected).to_lowercase())
+        }
+        (op, Some(actual), Some(expected)) => {
+            let ordering = compare_values(actual, &expected);
+            match op {
+                PropertyOperator::Eq => ordering == Some(Ordering::Equal),
+                PropertyOperator::Ne => ordering != Some(Ordering::Equal),
+                PropertyOperator::Lt => ordering == Some(Ordering::Less),
+                PropertyOperator::Lte => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                PropertyOperator::Gt => ordering == Some(Ordering::Greater),
+                PropertyOperator::Gte => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                _ => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+
+    #[test]
+    fn test_infer_property_type() {
+        assert_eq!(infer_property_type(&Value::from("in-progress")), PropertyType::Text);
+        assert_eq!(infer_property_type(&Value::from(2)), PropertyType::Number);
+        assert_eq!(infer_property_type(&Value::from("2026-11-01")), PropertyType::Date);
+        assert_eq!(infer_property_type(&Value::from("2026-10-20T09:00")), PropertyType::DateTime);
+        assert_eq!(infer_property_type(&Value::from("https://example.com")), PropertyType::Url);
+        assert_eq!(infer_property_type(&coerce_property_value(Value::from("true"))), PropertyType::Boolean);
+    }
+
+    #[tokio::test]
+    async fn test_properties_round_trip_and_query() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let mut ids = Vec::new();
+        for (title, priority) in [("Low", "3"), ("High", "1"), ("None", "")] {
+            let note = vault.create_note(title, Some(""), &[]).await;
+            if !priority.is_empty() {
+                manager.set_note_property(&note.id, "priority", Value::from(priority)).await.unwrap();
+            }
+            ids.push(note.id);
+        }
+        manager.set_note_property(&ids[0], "status", Value::from("in-progress")).await.unwrap();
+
+        let note = manager.get_note(&ids[1]).await.unwrap().unwrap();
+        assert_eq!(note.properties.get("priority"), Some(&Value::from(1)));
+        assert!(manager.set_note_property(&ids[1], "title", Value::from("x")).await.is_err());
+        let hijack = BTreeMap::from([("title".to_string(), Value::from("Hijack"))]);
+        assert!(manager.update_properties(&ids[1], hijack).await.is_err());
+        assert_eq!(manager.get_note(&ids[1]).await.unwrap().unwrap().title, "High");
+        for key in ["id", "remind", "template", "archived"] {
+            assert!(reserved_keys().contains(key));
+        }
+
+        let sorted = manager.query_notes_by_property(&PropertyQuery {
+            filters: vec![],
+            sort_by: Some("priority".to_string()),
+            descending: false,
+            include_archived: false,
+        }).await.unwrap();
+        let titles: Vec<&str> = sorted.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, vec!["High", "Low", "None"]);
+
+        let filtered = manager.query_notes_by_property(&PropertyQuery {
+            filters: vec![PropertyFilter { key: "priority".to_string(), op: PropertyOperator::Gt, value: Some(Value::from("1")) }],
+            sort_by: None,
+            descending: false,
+            include_archived: false,
+        }).await.unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].title, "Low");
+
+        manager.unset_note_property(&ids[0], "status").await.unwrap();
+        let summary = manager.get_vault_properties().await.unwrap();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].key, "priority");
+        assert_eq!(summary[0].property_type, PropertyType::Number);
+        assert_eq!(summary[0].note_count, 2);
+    }
+}
@@ -0,0 +1,120 @@
+use super::NotesManager;
+use crate::models::{Note, Reminder};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone};
+
+// Format written back to front matter, matching what users type by hand
+const REMIND_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+impl NotesManager {
+    /// Lists pending reminders on notes outside the trash, archived ones included, earliest first.
+    pub async fn get_reminders(&self) -> Result<Vec<Reminder>> {
+        let now = Local::now();
+        let mut reminders: Vec<(DateTime<Local>, Reminder)> = Vec::new();
+
+        for note in self.get_live_notes().await? {
+            let remind = match &note.remind {
+                Some(remind) => remind,
+                None => continue,
+            };
+            match parse_remind_time(remind) {
+                Some(at) => reminders.push((at, Reminder {
+                    note_id: note.id,
+                    note_title: note.title,
+                    remind_at: at.to_rfc3339(),
+                    overdue: at <= now,
+                })),
+                None => log::warn!("Ignoring unparseable reminder '{}' on note {}", remind, note.id),
+            }
+        }
+
+        reminders.sort_by_key(|r| r.0);
+        Ok(reminders.into_iter().map(|(_, r)| r).collect())
+    }
+
+    pub async fn get_due_reminders(&self) -> Result<Vec<Reminder>> {
+        Ok(self.get_reminders().await?.into_iter().filter(|r| r.overdue).collect())
+    }
+
+    pub async fn set_reminder(&self, note_id: &str, remind_at: &str) -> Result<Option<Note>> {
+        let at = parse_remind_time(remind_at)
+            .ok_or_else(|| anyhow::anyhow!("Invalid reminder time: {}", remind_at))?;
+        self.write_remind(note_id, Some(at.format(REMIND_FORMAT).to_string())).await
+    }
+
+    pub async fn snooze_reminder(&self, note_id: &str, minutes: i64) -> Result<Option<Note>> {
+        if minutes <= 0 {
+            return Err(anyhow::anyhow!("Snooze duration must be positive"));
+        }
+        let at = Local::now() + Duration::minutes(minutes);
+        self.write_remind(note_id, Some(at.format(REMIND_FORMAT).to_string())).await
+    }
+
+    pub async fn dismiss_reminder(&self, note_id: &str) -> Result<Option<Note>> {
+        self.write_remind(note_id, None).await
+    }
+
+    // Reminder bookkeeping isn't a content edit, so `modified` is left untouched
+    async fn write_remind(&self, note_id: &str, remind: Option<String>) -> Result<Option<Note>> {
+        let verb = if remind.is_some() { "Set a reminder on" } else { "Dismissed the reminder on" };
+        let change = match self.change_metadata(note_id, |metadata| metadata.remind = remind)? {
+            Some(change) => change,
+            None => return Ok(None),
+        };
+        self.record_change("set_reminder", verb, change)?;
+        self.get_note(note_id).await
+    }
+}
+
+/// Parses a reminder written as local time ("2026-10-20T09:00"), RFC 3339, or a bare date (09:00 that day).
+pub fn parse_remind_time(value: &str) -> Option<DateTime<Local>> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Local));
+    }
+
+    let naive = ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.and_hms_opt(9, 0, 0))?;
+
+    Local.from_local_datetime(&naive).earliest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+
+    #[test]
+    fn test_parse_remind_time() {
+        let at = parse_remind_time("2026-10-20T09:00").unwrap();
+        assert_eq!(at.format(REMIND_FORMAT).to_string(), "2026-10-20T09:00");
+
+        let date_only = parse_remind_time("2026-10-20").unwrap();
+        assert_eq!(date_only.format(REMIND_FORMAT).to_string(), "2026-10-20T09:00");
+
+        assert!(parse_remind_time("tomorrow").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_due_snooze_and_dismiss() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let note = vault.create_note("Call dentist", None, &[]).await;
+
+        manager.set_reminder(&note.id, "2020-01-01T09:00").await.unwrap();
+        let due = manager.get_due_reminders().await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].note_id, note.id);
+
+        manager.snooze_reminder(&note.id, 10).await.unwrap();
+        assert!(manager.get_due_reminders().await.unwrap().is_empty());
+        assert_eq!(manager.get_reminders().await.unwrap().len(), 1);
+
+        let dismissed = manager.dismiss_reminder(&note.id).await.unwrap().unwrap();
+        assert!(dismissed.remind.is_none());
+        assert!(manager.get_reminders().await.unwrap().is_empty());
+    }
+}
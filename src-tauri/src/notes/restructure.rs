@@ -0,0 +1,223 @@
+use super::outline::{content_update, parse_headings};
+use super::NotesManager;
+use crate::models::{CreateNoteRequest, Note, UpdateNoteRequest};
+use crate::storage::note_change;
+use anyhow::Result;
+
+impl NotesManager {
+    /// Copies a note's content, tags, properties and attachment references into a new note.
+    pub async fn duplicate_note(&self, note_id: &str) -> Result<Option<Note>> {
+        let source = match self.get_note(note_id).await? {
+            Some(note) => note,
+            None => return Ok(None),
+        };
+        Self::ensure_not_encrypted(&source)?;
+
+        let (copy, _) = self.save_new_note(CreateNoteRequest {
+            title: format!("{} (copy)", source.title),
+            content: Some(source.content),
+            tags: Some(source.tags),
+            template: None,
+        })?;
+
+        // Attachment files are shared rather than copied; a pending reminder is not carried over
+        let copy = self.update_metadata(&copy.id, |metadata| {
+            metadata.attachments = source.attachments;
+            metadata.properties = source.properties;
+            metadata.favorite = source.is_favorite;
+        })?;
+        if let Some(copy) = &copy {
+            let change = note_change(None, self.storage.find_note_file(&copy.id)?.as_ref());
+            self.record_change("duplicate_note", "Duplicated", change)?;
+        }
+        Ok(copy)
+    }
+
+    /// Appends each source note to `target_id` under its title, unions tags and
+    /// attachments, then moves the sources to the trash.
+    pub async fn merge_notes(&self, note_ids: &[String], target_id: &str) -> Result<Option<Note>> {
+        let target = match self.get_note(target_id).await? {
+            Some(note) => note,
+            None => return Ok(None),
+        };
+        Self::ensure_unlocked(&target)?;
+        Self::ensure_not_encrypted(&target)?;
+
+        let mut sources: Vec<Note> = Vec::new();
+        for id in note_ids {
+            if id == target_id || sources.iter().any(|n| &n.id == id) {
+                continue;
+            }
+            match self.get_note(id).await? {
+                // Sources go to the trash, which a locked note refuses
+                Some(note) => {
+                    Self::ensure_unlocked(&note)?;
+                    Self::ensure_not_encrypted(&note)?;
+                    sources.push(note);
+                }
+                None => return Err(anyhow::anyhow!("Note {} not found", id)),
+            }
+        }
+        if sources.is_empty() {
+            return Err(anyhow::anyhow!("No notes to merge into {}", target_id));
+        }
+
+        let mut content = target.content.trim_end().to_string();
+        let mut tags = target.tags;
+        let mut attachments = target.attachments;
+        let mut properties = target.properties;
+
+        for source in &sources {
+            if !content.is_empty() {
+                content.push_str("\n\n");
+            }
+            content.push_str(&format!("# {}\n\n{}", source.title, source.content.trim()));
+
+            for tag in &source.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+            for attachment in &source.attachments {
+                if !attachments.contains(attachment) {
+                    attachments.push(attachment.clone());
+                }
+            }
+            // The target's own values win on conflicting keys
+            for (key, value) in &source.properties {
+                properties.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+        content.push('\n');
+
+        // Write the merged note before trashing anything so a failure loses nothing
+        let before = self.storage.find_note_file(target_id)?;
+        self.apply_update(UpdateNoteRequest {
+            id: target_id.to_string(),
+            title: None,
+            content: Some(content),
+            is_favorite: None,
+            tags: Some(tags),
+            properties: Some(properties),
+        }).await?;
+        let merged = self.update_metadata(target_id, |metadata| metadata.attachments = attachments)?;
+
+        let after = self.storage.find_note_file(target_id)?;
+
+        let mut changes = vec![note_change(before.as_ref(), after.as_ref())];
+        for source in &sources {
+            changes.extend(self.set_note_deleted(&source.id, true)?);
+        }
+        let summary = format!("Merged {} notes into '{}'", sources.len(), target.title);
+        self.storage.record_operation("merge_notes", summary, changes)?;
+
+        Ok(merged)
+    }
+
+    /// Moves every section at `heading_level` into its own note, leaving a link
+    /// to the new note where the section was. Returns the created notes.
+    pub async fn split_note(&self, note_id: &str, heading_level: u8) -> Result<Vec<Note>> {
+        if !(1..=6).contains(&heading_level) {
+            return Err(anyhow::anyhow!("Heading level must be between 1 and 6"));
+        }
+
+        let note = self.get_note(note_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Note {} not found", note_id))?;
+        Self::ensure_unlocked(&note)?;
+        Self::ensure_not_encrypted(&note)?;
+        let spans: Vec<_> = parse_headings(&note.content)
+            .into_iter()
+            .filter(|s| s.level == heading_level)
+            .collect();
+        if spans.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let before = self.storage.find_note_file(note_id)?;
+        let mut changes = Vec::new();
+        let mut created = Vec::new();
+        let mut remaining = String::new();
+        let mut cursor = 0;
+
+        for span in &spans {
+            let body = note.content[span.heading_end.min(span.section_end)..span.section_end].trim();
+            let (part, _) = self.save_new_note(CreateNoteRequest {
+                title: span.text.clone(),
+                content: Some(format!("{}\n", body)),
+                tags: Some(note.tags.clone()),
+                template: None,
+            })?;
+
+            let attachments: Vec<String> = note.attachments.iter()
+                .filter(|a| body.contains(a.as_str()))
+                .cloned()
+                .collect();
+            let part = if attachments.is_empty() {
+                part
+            } else {
+                self.update_metadata(&part.id, |metadata| metadata.attachments = attachments)?.unwrap_or(part)
+            };
+
+            remaining.push_str(&note.content[cursor..span.start]);
+            remaining.push_str(&format!("[{}](<{}>)\n", part.title, part.file_path));
+            if span.section_end < note.content.len() {
+                remaining.push('\n');
+            }
+            cursor = span.section_end;
+            changes.push(note_change(None, self.storage.find_note_file(&part.id)?.as_ref()));
+            created.push(part);
+        }
+        remaining.push_str(&note.content[cursor..]);
+
+        self.apply_update(content_update(note_id, remaining)).await?;
+        let after = self.storage.find_note_file(note_id)?;
+        changes.insert(0, note_change(before.as_ref(), after.as_ref()));
+        let summary = format!("Split '{}' into {} notes", note.title, created.len());
+        self.storage.record_operation("split_note", summary, changes)?;
+        Ok(created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::test_vault;
+    use crate::models::NoteSort;
+
+    #[tokio::test]
+    async fn test_duplicate_and_merge_notes() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let original = vault.create_note("Plan", Some("step one"), &["work"]).await;
+        manager.add_attachment_to_note(&original.id, "attachments/a.png").await.unwrap();
+
+        let copy = manager.duplicate_note(&original.id).await.unwrap().unwrap();
+        assert_ne!(copy.id, original.id);
+        assert_eq!(copy.title, "Plan (copy)");
+        assert_eq!(copy.content, "step one");
+        assert_eq!(copy.attachments, vec!["attachments/a.png".to_string()]);
+
+        let other = vault.create_note("Ideas", Some("more"), &["work", "ideas"]).await;
+        let merged = manager.merge_notes(&[copy.id.clone(), other.id.clone()], &original.id).await.unwrap().unwrap();
+        assert_eq!(merged.content, "step one\n\n# Plan (copy)\n\nstep one\n\n# Ideas\n\nmore\n");
+        assert_eq!(merged.tags, vec!["work".to_string(), "ideas".to_string()]);
+        assert_eq!(merged.attachments, vec!["attachments/a.png".to_string()]);
+        assert_eq!(manager.get_trash(NoteSort::Modified).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_split_note_leaves_links() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let note = vault.create_note("Book", Some("# Book\nintro\n## One\nfirst\n## Two\nsecond\n"), &["draft"]).await;
+        let parts = manager.split_note(&note.id, 2).await.unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].title, "One");
+        assert_eq!(parts[0].content, "first\n");
+        assert_eq!(parts[1].tags, vec!["draft".to_string()]);
+
+        let original = manager.get_note(&note.id).await.unwrap().unwrap();
+        assert_eq!(original.content, format!("# Book\nintro\n[One](<{}>)\n\n[Two](<{}>)\n", parts[0].file_path, parts[1].file_path));
+    }
+}
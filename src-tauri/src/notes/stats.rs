@@ -0,0 +1,269 @@
+use super::NotesManager;
+use crate::models::{AttachmentSize, NoteSize, NoteSort, VaultStats};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+// How many entries the "largest" lists return
+const LARGEST_LIMIT: usize = 10;
+
+// Reading speeds used for Note.reading_time
+const WORDS_PER_MINUTE: f64 = 200.0;
+const CJK_CHARS_PER_MINUTE: f64 = 300.0;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextCounts {
+    // Space-separated words plus one per CJK character
+    pub words: usize,
+    // Non-whitespace characters
+    pub characters: usize,
+    pub cjk_characters: usize,
+}
+
+impl NotesManager {
+    /// Computes vault statistics. The heatmap comes from `git_activity` (note
+    /// files changed per day in commit history) when there is any, otherwise
+    /// from the days each note was created or edited, each note counted once a day.
+    /// Archived notes count as part of the vault; trashed ones only in `trashed_count`.
+    pub async fn get_vault_stats(&self, git_activity: Option<&BTreeMap<String, usize>>) -> Result<VaultStats> {
+        let notes = self.get_live_notes().await?;
+        let trashed_count = self.get_trash(NoteSort::Modified).await?.len();
+
+        let mut tags: HashSet<&str> = HashSet::new();
+        let mut total_words = 0;
+        let mut total_characters = 0;
+        let mut created_per_day: BTreeMap<String, usize> = BTreeMap::new();
+        let mut edited_per_day: BTreeMap<String, usize> = BTreeMap::new();
+        let mut active_per_day: BTreeMap<String, usize> = BTreeMap::new();
+        let mut largest_notes: Vec<NoteSize> = Vec::new();
+
+        for note in &notes {
+            tags.extend(note.tags.iter().map(|t| t.as_str()));
+
+            // Encrypted bodies stay out of the totals, unlocked or not, so the
+            // numbers don't move with the session
+            if !note.is_encrypted {
+                let counts = count_text(&note.content);
+                total_words += counts.words;
+                total_characters += counts.characters;
+                largest_notes.push(NoteSize {
+                    id: note.id.clone(),
+                    title: note.title.clone(),
+                    word_count: counts.words,
+                    bytes: note.content.len() as u64,
+                });
+            }
+
+            // Version snapshots are taken on save, so they mark earlier edits
+            let mut edit_days: BTreeSet<String> = self.storage.list_history(&note.id)
+                .unwrap_or_default()
+                .iter()
+                .map(|entry| local_day(&entry.created_at))
+                .collect();
+            edit_days.insert(local_day(&note.modified_at));
+            for day in &edit_days {
+                *edited_per_day.entry(day.clone()).or_insert(0) += 1;
+            }
+
+            let created_day = local_day(&note.created_at);
+            *created_per_day.entry(created_day.clone()).or_insert(0) += 1;
+            edit_days.insert(created_day);
+            for day in edit_days {
+                *active_per_day.entry(day).or_insert(0) += 1;
+            }
+        }
+
+        largest_notes.sort_by_key(|n| std::cmp::Reverse(n.bytes));
+        largest_notes.truncate(LARGEST_LIMIT);
+
+        let mut attachments = Vec::new();
+        collect_attachments(&self.storage.notes_directory, &self.storage.notes_directory.join("attachments"), &mut attachments)?;
+        let attachment_bytes = attachments.iter().map(|a| a.bytes).sum();
+        let attachment_count = attachments.len();
+        attachments.sort_by_key(|a| std::cmp::Reverse(a.bytes));
+        attachments.truncate(LARGEST_LIMIT);
+
+        let heatmap = match git_activity {
+            Some(activity) if !activity.is_empty() => activity.clone(),
+            _ => active_per_day,
+        };
+
+        let (current_streak, longest_streak) = writing_streaks(&heatmap, Local::now().date_naive());
+
+        Ok(VaultStats {
+            note_count: notes.len(),
+            trashed_count,
+            tag_count: tags.len(),
+            attachment_count,
+            attachment_bytes,
+            total_words,
+            total_characters,
+            created_per_day,
+            edited_per_day,
+            heatmap,
+            current_streak,
+            longest_streak,
+            largest_notes,
+            largest_attachments: attachments,
+        })
+    }
+}
+
+fn local_day(at: &DateTime<chrono::Utc>) -> String {
+    at.with_timezone(&Local).format("%Y-%m-%d").to_string()
+}
+
+fn collect_attachments(root: &Path, dir: &Path, out: &mut Vec<AttachmentSize>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_attachments(root, &path, out)?;
+        } else if path.is_file() {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            out.push(AttachmentSize {
+                path: relative.to_string_lossy().replace('\\', "/"),
+                bytes: fs::metadata(&path)?.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns (current, longest) runs of consecutive active days. The current
+/// streak still counts if today has no activity yet but yesterday did.
+fn writing_streaks(activity: &BTreeMap<String, usize>, today: NaiveDate) -> (usize, usize) {
+    let days: Vec<NaiveDate> = activity.iter()
+        .filter(|(_, count)| **count > 0)
+        .filter_map(|(day, _)| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+        .collect();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in &days {
+        run = match previous {
+            Some(prev) if *day - prev == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+
+    let current = match days.last() {
+        Some(last) if *last == today || *last == today - Duration::days(1) => run,
+        _ => 0,
+    };
+
+    (current, longest)
+}
+
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF     // CJK Unified Ideographs
+        | 0x3400..=0x4DBF   // Extension A
+        | 0x20000..=0x2EBEF // Extensions B-F
+        | 0xF900..=0xFAFF   // Compatibility Ideographs
+        | 0x3040..=0x309F   // Hiragana
+        | 0x30A0..=0x30FF   // Katakana
+    )
+}
+
+pub fn count_text(text: &str) -> TextCounts {
+    let mut counts = TextCounts::default();
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if c.is_whitespace() {
+            in_word = false;
+            continue;
+        }
+        counts.characters += 1;
+
+        if is_cjk(c) {
+            counts.cjk_characters += 1;
+            counts.words += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                counts.words += 1;
+                in_word = true;
+            }
+        } else if c != '\'' && c != '-' {
+            // Punctuation splits words but apostrophes and hyphens join them
+            in_word = false;
+        }
+    }
+
+    counts
+}
+
+/// Estimated reading time in whole minutes (at least 1 for non-empty notes).
+pub fn reading_time_minutes(counts: &TextCounts) -> u32 {
+    let latin_words = counts.words - counts.cjk_characters;
+    let minutes = latin_words as f64 / WORDS_PER_MINUTE + counts.cjk_characters as f64 / CJK_CHARS_PER_MINUTE;
+    if counts.words == 0 {
+        0
+    } else {
+        minutes.ceil().max(1.0) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+
+    #[test]
+    fn test_count_text_handles_cjk() {
+        let counts = count_text("Hello, world! 你好世界 don't re-read");
+        assert_eq!(counts.words, 8);
+        assert_eq!(counts.cjk_characters, 4);
+        assert_eq!(counts.characters, 28);
+
+        assert_eq!(reading_time_minutes(&count_text("")), 0);
+        assert_eq!(reading_time_minutes(&count_text("one two")), 1);
+        assert_eq!(reading_time_minutes(&count_text(&"字".repeat(601))), 3);
+    }
+
+    #[test]
+    fn test_writing_streaks() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let activity: BTreeMap<String, usize> = ["2026-10-01", "2026-10-02", "2026-10-03", "2026-10-16", "2026-10-17"]
+            .iter()
+            .map(|d| (d.to_string(), 1))
+            .collect();
+
+        assert_eq!(writing_streaks(&activity, today), (2, 3));
+        assert_eq!(writing_streaks(&activity, today + Duration::days(2)), (0, 3));
+        assert_eq!(writing_streaks(&BTreeMap::new(), today), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_heatmap_counts_each_note_once_per_day() {
+        let vault = test_vault();
+        let manager = &vault.notes;
+
+        let note = vault.create_note("Today", Some("first"), &[]).await;
+        manager.update_note(crate::models::UpdateNoteRequest {
+            id: note.id.clone(),
+            title: None,
+            content: Some("second".to_string()),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        }).await.unwrap();
+
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let stats = manager.get_vault_stats(None).await.unwrap();
+        assert_eq!(stats.heatmap.get(&today), Some(&1));
+        assert_eq!(stats.edited_per_day.get(&today), Some(&1));
+
+        let git: BTreeMap<String, usize> = [("2026-01-05".to_string(), 3)].into_iter().collect();
+        assert_eq!(manager.get_vault_stats(Some(&git)).await.unwrap().heatmap, git);
+    }
+}
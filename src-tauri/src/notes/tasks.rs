@@ -0,0 +1,215 @@
+use super::NotesManager;
+use crate::models::{Task, TaskFilter, UpdateNoteRequest};
+use anyhow::Result;
+use regex::Regex;
+
+struct ParsedTask {
+    line: usize,
+    text: String,
+    done: bool,
+    due: Option<String>,
+    tags: Vec<String>,
+}
+
+impl NotesManager {
+    pub async fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
+        let notes = match &filter.note_id {
+            Some(note_id) => self.get_note(note_id).await?.into_iter().collect(),
+            None if filter.include_archived => self.get_live_notes().await?,
+            None => self.get_all_notes().await?,
+        };
+
+        let query = filter.query.as_ref().map(|q| q.to_lowercase());
+        let mut tasks = Vec::new();
+
+        for note in notes {
+            for parsed in extract_tasks(&note.content) {
+                if let Some(done) = filter.done {
+                    if parsed.done != done {
+                        continue;
+                    }
+                }
+                if let Some(tag) = &filter.tag {
+                    if !parsed.tags.contains(tag) && !note.tags.contains(tag) {
+                        continue;
+                    }
+                }
+                if let Some(due_before) = &filter.due_before {
+                    match &parsed.due {
+                        Some(due) if due <= due_before => {}
+                        _ => continue,
+                    }
+                }
+                if let Some(query) = &query {
+                    if !parsed.text.to_lowercase().contains(query) {
+                        continue;
+                    }
+                }
+
+                tasks.push(Task {
+                    note_id: note.id.clone(),
+                    note_title: note.title.clone(),
+                    line: parsed.line,
+                    text: parsed.text,
+                    done: parsed.done,
+                    due: parsed.due,
+                    tags: parsed.tags,
+                });
+            }
+        }
+
+        // Dated tasks first (earliest due), then by note and position
+        tasks.sort_by(|a, b| match (&a.due, &b.due) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a.note_title.cmp(&b.note_title).then(a.line.cmp(&b.line)),
+        });
+
+        Ok(tasks)
+    }
+
+    /// Flips the checkbox on `line` (1-based, relative to the note body) and saves the note.
+    pub async fn toggle_task(&self, note_id: &str, line: usize) -> Result<Option<Task>> {
+        let note = match self.get_note(note_id).await? {
+            Some(note) => note,
+            None => return Ok(None),
+        };
+
+        let content = match toggle_task_line(&note.content, line) {
+            Some(content) => content,
+            None => return Err(anyhow::anyhow!("Line {} of note {} is not a task", line, note_id)),
+        };
+
+        let updated = self.update_and_record("toggle_task", "Toggled a task in", UpdateNoteRequest {
+            id: note_id.to_string(),
+            title: None,
+            content: Some(content),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        }).await?;
+
+        Ok(updated.and_then(|note| {
+            extract_tasks(&note.content).into_iter().find(|t| t.line == line).map(|parsed| Task {
+                note_id: note.id.clone(),
+                note_title: note.title.clone(),
+                line: parsed.line,
+                text: parsed.text,
+                done: parsed.done,
+                due: parsed.due,
+                tags: parsed.tags,
+            })
+        }))
+    }
+}
+
+fn task_regex() -> Regex {
+    Regex::new(r"^(\s*(?:[-*+]|\d+[.)])\s+\[)([ xX])(\]\s+)(.*)$").unwrap()
+}
+
+fn extract_tasks(content: &str) -> Vec<ParsedTask> {
+    let task_re = task_regex();
+    let due_re = Regex::new(r"@due\((\d{4}-\d{2}-\d{2})\)").unwrap();
+    let tag_re = Regex::new(r"(?:^|\s)#([\w/-]+)").unwrap();
+
+    let mut tasks = Vec::new();
+    let mut in_fence = false;
+
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        if let Some(caps) = task_re.captures(line) {
+            let raw_text = caps.get(4).map(|m| m.as_str()).unwrap_or("");
+            let due = due_re.captures(raw_text).map(|c| c[1].to_string());
+            let tags: Vec<String> = tag_re.captures_iter(raw_text).map(|c| c[1].to_string()).collect();
+
+            let text = tag_re.replace_all(&due_re.replace_all(raw_text, ""), "").trim().to_string();
+
+            tasks.push(ParsedTask {
+                line: idx + 1,
+                text,
+                done: &caps[2] != " ",
+                due,
+                tags,
+            });
+        }
+    }
+
+    tasks
+}
+
+fn toggle_task_line(content: &str, line: usize) -> Option<String> {
+    if line == 0 || extract_tasks(content).iter().all(|t| t.line != line) {
+        return None;
+    }
+
+    let task_re = task_regex();
+    let mut result = String::with_capacity(content.len());
+
+    // split_inclusive keeps the original line endings (including CRLF) intact
+    for (idx, raw_line) in content.split_inclusive('\n').enumerate() {
+        if idx + 1 != line {
+            result.push_str(raw_line);
+            continue;
+        }
+
+        let (body, ending) = match raw_line.strip_suffix("\r\n") {
+            Some(body) => (body, "\r\n"),
+            None => match raw_line.strip_suffix('\n') {
+                Some(body) => (body, "\n"),
+                None => (raw_line, ""),
+            },
+        };
+
+        let toggled = task_re.replace(body, |caps: &regex::Captures| {
+            let mark = if &caps[2] == " " { "x" } else { " " };
+            format!("{}{}{}{}", &caps[1], mark, &caps[3], &caps[4])
+        });
+        result.push_str(&toggled);
+        result.push_str(ending);
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_tasks() {
+        let content = "# Meeting\n- [ ] Send notes @due(2026-11-01) #work\n* [x] Book room\n```\n- [ ] not a task\n```\n1. [ ] Numbered";
+        let tasks = extract_tasks(content);
+
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].line, 2);
+        assert_eq!(tasks[0].text, "Send notes");
+        assert_eq!(tasks[0].due.as_deref(), Some("2026-11-01"));
+        assert_eq!(tasks[0].tags, vec!["work".to_string()]);
+        assert!(!tasks[0].done);
+        assert!(tasks[1].done);
+        assert_eq!(tasks[2].line, 7);
+    }
+
+    #[test]
+    fn test_toggle_task_line_preserves_rest_of_file() {
+        let content = "intro\r\n- [ ] first\r\n  - [X] nested\r\ntrailing";
+
+        let toggled = toggle_task_line(content, 2).unwrap();
+        assert_eq!(toggled, "intro\r\n- [x] first\r\n  - [X] nested\r\ntrailing");
+
+        let toggled = toggle_task_line(&toggled, 3).unwrap();
+        assert_eq!(toggled, "intro\r\n- [x] first\r\n  - [ ] nested\r\ntrailing");
+
+        assert!(toggle_task_line(content, 1).is_none());
+        assert!(toggle_task_line(content, 99).is_none());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::models::Reminder;
+use crate::notes::NotesManager;
+use crate::{log_debug, log_error, log_warn};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+pub const REMINDER_EVENT: &str = "reminder-due";
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Polls note front matter for due reminders. The first pass runs immediately,
+// so reminders that came due while the app was closed fire at startup.
+pub fn start_reminder_scheduler(app_handle: AppHandle, notes_manager: Arc<NotesManager>) {
+    tauri::async_runtime::spawn(async move {
+        // (note id, reminder time) pairs already announced this session
+        let mut fired: HashSet<(String, String)> = HashSet::new();
+        
+        loop {
+            match notes_manager.get_due_reminders().await {
+                Ok(due) => {
+                    // Forget dismissed or snoozed reminders so a new time fires again
+                    fired.retain(|(id, at)| due.iter().any(|r| &r.note_id == id && &r.remind_at == at));
+                    
+                    for reminder in due {
+                        if fired.insert((reminder.note_id.clone(), reminder.remind_at.clone())) {
+                            notify(&app_handle, &reminder);
+                        }
+                    }
+                }
+                Err(e) => log_error!("Failed to check reminders: {}", e),
+            }
+            
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn notify(app_handle: &AppHandle, reminder: &Reminder) {
+    log_debug!("Reminder due for note {}: {}", reminder.note_id, reminder.note_title);
+    
+    if let Err(e) = app_handle.emit_all(REMINDER_EVENT, reminder.clone()) {
+        log_warn!("Failed to emit reminder event for note {}: {}", reminder.note_id, e);
+    }
+    
+    let identifier = app_handle.config().tauri.bundle.identifier.clone();
+    if let Err(e) = tauri::api::notification::Notification::new(identifier)
+        .title(reminder.note_title.clone())
+        .body(format!("Reminder: {}", reminder.note_title))
+        .show()
+    {
+        log_warn!("Failed to show reminder notification for note {}: {}", reminder.note_id, e);
+    }
+}
@@ -0,0 +1,127 @@
+use super::FileStorageManager;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const DRAFTS_DIR_NAME: &str = "drafts";
+const DRAFT_JOURNAL_NAME: &str = "drafts.jsonl";
+// Past this size the journal is rewritten with only the newest draft per note
+const COMPACT_THRESHOLD_BYTES: u64 = 4 * 1024 * 1024;
+
+// Appends and rewrites must not interleave
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftEntry {
+    pub note_id: String,
+    pub content: String,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl FileStorageManager {
+    // Drafts hold unsaved bodies, so they stay out of the notes folder when the
+    // app gives them a place, and otherwise in a folder git sync ignores
+    fn draft_journal_path(&self) -> Result<PathBuf> {
+        let dir = match &self.drafts_directory {
+            Some(dir) => dir,
+            None => return self.local_file(DRAFTS_DIR_NAME, DRAFT_JOURNAL_NAME),
+        };
+        fs::create_dir_all(dir).context("Failed to create drafts directory")?;
+        let path = dir.join(DRAFT_JOURNAL_NAME);
+        if !path.exists() {
+            // Moves in a journal earlier versions kept in the vault; copied, as
+            // the two may be on different drives
+            let vault = self.vault_directory();
+            let legacy = [vault.join(DRAFTS_DIR_NAME).join(DRAFT_JOURNAL_NAME), vault.join(DRAFT_JOURNAL_NAME)];
+            if let Some(legacy) = legacy.iter().find(|p| p.exists()) {
+                fs::copy(legacy, &path).context("Failed to move draft journal")?;
+                fs::remove_file(legacy).context("Failed to move draft journal")?;
+            }
+        }
+        Ok(path)
+    }
+
+    /// Appends one line to the journal; the note file itself is not touched.
+    pub fn append_draft(&self, note_id: &str, content: &str) -> Result<()> {
+        let entry = DraftEntry {
+            note_id: note_id.to_string(),
+            content: content.to_string(),
+            saved_at: Utc::now(),
+        };
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+
+        let _guard = JOURNAL_LOCK.lock().unwrap();
+        let path = self.draft_journal_path()?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)
+            .context("Failed to open draft journal")?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+
+        if file.metadata()?.len() > COMPACT_THRESHOLD_BYTES {
+            let latest = self.read_latest_drafts()?;
+            self.write_drafts(latest.values())?;
+        }
+        Ok(())
+    }
+
+    /// Newest draft per note.
+    pub fn latest_drafts(&self) -> Result<HashMap<String, DraftEntry>> {
+        let _guard = JOURNAL_LOCK.lock().unwrap();
+        self.read_latest_drafts()
+    }
+
+    /// Rewrites the journal keeping only drafts that pass `keep`.
+    pub fn retain_drafts<F: Fn(&DraftEntry) -> bool>(&self, keep: F) -> Result<()> {
+        let _guard = JOURNAL_LOCK.lock().unwrap();
+        let latest = self.read_latest_drafts()?;
+        self.write_drafts(latest.values().filter(|d| keep(d)))
+    }
+
+    fn read_latest_drafts(&self) -> Result<HashMap<String, DraftEntry>> {
+        let path = self.draft_journal_path()?;
+        let mut latest: HashMap<String, DraftEntry> = HashMap::new();
+        if !path.exists() {
+            return Ok(latest);
+        }
+
+        let text = fs::read_to_string(&path).context("Failed to read draft journal")?;
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            // A crash can leave a torn last line; skip anything unreadable
+            match serde_json::from_str::<DraftEntry>(line) {
+                Ok(entry) => {
+                    let older = latest.get(&entry.note_id).is_some_and(|d| d.saved_at > entry.saved_at);
+                    if !older {
+                        latest.insert(entry.note_id.clone(), entry);
+                    }
+                }
+                Err(e) => log::warn!("Skipping unreadable draft journal line: {}", e),
+            }
+        }
+        Ok(latest)
+    }
+
+    fn write_drafts<'a, I: Iterator<Item = &'a DraftEntry>>(&self, drafts: I) -> Result<()> {
+        let mut text = String::new();
+        for draft in drafts {
+            text.push_str(&serde_json::to_string(draft)?);
+            text.push('\n');
+        }
+
+        let path = self.draft_journal_path()?;
+        if text.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, text).context("Failed to write draft journal")?;
+        fs::rename(&temp_path, &path).context("Failed to write draft journal")
+    }
+}
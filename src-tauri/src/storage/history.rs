@@ -0,0 +1,147 @@
+use super::FileStorageManager;
+use crate::models::NoteMetadata;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+const HISTORY_DIR_NAME: &str = "history";
+const SNAPSHOT_EXTENSION: &str = ".md.gz";
+// Version ids are snapshot times, which also sort chronologically
+const VERSION_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub version_id: String,
+    pub created_at: DateTime<Utc>,
+    pub bytes: u64,
+}
+
+impl FileStorageManager {
+    fn history_directory(&self, note_id: &str) -> Result<PathBuf> {
+        // Ids come from front matter, so never let one escape the history folder
+        if note_id.is_empty() || !note_id.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+            return Err(anyhow::anyhow!("Invalid note id for history: {}", note_id));
+        }
+        Ok(self.vault_directory().join(HISTORY_DIR_NAME).join(note_id))
+    }
+
+    /// Stores `text` (a whole note file) as a snapshot. Unless `force` is set,
+    /// nothing is written if the newest snapshot is inside the throttle window.
+    pub fn snapshot_note(&self, note_id: &str, text: &str, force: bool) -> Result<Option<HistoryEntry>> {
+        let settings = self.load_vault_settings()?.history;
+        if !settings.enabled {
+            return Ok(None);
+        }
+
+        let dir = self.history_directory(note_id)?;
+        let now = Utc::now();
+        if let Some(newest) = self.list_history(note_id)?.first() {
+            if !force && now - newest.created_at < Duration::minutes(settings.min_interval_minutes) {
+                return Ok(None);
+            }
+            if self.read_history(note_id, &newest.version_id)?.as_deref() == Some(text) {
+                return Ok(None);
+            }
+        }
+
+        // Snapshots are local only; keep them out of git sync commits
+        self.local_directory(HISTORY_DIR_NAME)?;
+        fs::create_dir_all(&dir).context("Failed to create history directory")?;
+        let version_id = now.format(VERSION_FORMAT).to_string();
+        let path = dir.join(format!("{}{}", version_id, SNAPSHOT_EXTENSION));
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes())?;
+        let compressed = encoder.finish()?;
+        fs::write(&path, &compressed).context("Failed to write history snapshot")?;
+
+        self.prune_history(note_id, settings.max_versions, settings.max_age_days)?;
+
+        Ok(Some(HistoryEntry { version_id, created_at: now, bytes: compressed.len() as u64 }))
+    }
+
+    /// Snapshots newest first.
+    pub fn list_history(&self, note_id: &str) -> Result<Vec<HistoryEntry>> {
+        let dir = self.history_directory(note_id)?;
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let version_id = match name.strip_suffix(SNAPSHOT_EXTENSION) {
+                Some(id) => id.to_string(),
+                None => continue,
+            };
+            if let Ok(created) = NaiveDateTime::parse_from_str(&version_id, VERSION_FORMAT) {
+                entries.push(HistoryEntry {
+                    version_id,
+                    created_at: Utc.from_utc_datetime(&created),
+                    bytes: entry.metadata()?.len(),
+                });
+            }
+        }
+
+        entries.sort_by(|a, b| b.version_id.cmp(&a.version_id));
+        Ok(entries)
+    }
+
+    /// Full note file text stored in a snapshot.
+    pub fn read_history(&self, note_id: &str, version_id: &str) -> Result<Option<String>> {
+        if NaiveDateTime::parse_from_str(version_id, VERSION_FORMAT).is_err() {
+            return Ok(None);
+        }
+        let path = self.history_directory(note_id)?.join(format!("{}{}", version_id, SNAPSHOT_EXTENSION));
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let mut text = String::new();
+        GzDecoder::new(fs::File::open(&path)?).read_to_string(&mut text)
+            .context("Failed to read history snapshot")?;
+        Ok(Some(text))
+    }
+
+    pub fn delete_history(&self, note_id: &str) -> Result<()> {
+        let dir = self.history_directory(note_id)?;
+        if dir.exists() {
+            fs::remove_dir_all(&dir).context("Failed to delete note history")?;
+        }
+        Ok(())
+    }
+
+    fn prune_history(&self, note_id: &str, max_versions: usize, max_age_days: i64) -> Result<()> {
+        let dir = self.history_directory(note_id)?;
+        let cutoff = Utc::now() - Duration::days(max_age_days);
+
+        for (i, entry) in self.list_history(note_id)?.into_iter().enumerate() {
+            // The newest snapshot always survives, however old it is
+            let too_many = max_versions > 0 && i >= max_versions;
+            let too_old = max_age_days > 0 && i > 0 && entry.created_at < cutoff;
+            if too_many || too_old {
+                fs::remove_file(dir.join(format!("{}{}", entry.version_id, SNAPSHOT_EXTENSION)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Splits a stored note file into front matter and body.
+pub fn parse_note_text(text: &str) -> Result<(NoteMetadata, String)> {
+    let rest = text.strip_prefix("---")
+        .ok_or_else(|| anyhow::anyhow!("Snapshot has no front matter"))?;
+    let end = rest.find("\n---")
+        .ok_or_else(|| anyhow::anyhow!("Snapshot front matter is not closed"))?;
+
+    let metadata: NoteMetadata = serde_yaml::from_str(rest[..end].trim())?;
+    let body = &rest[end + 4..];
+    let body = body.strip_prefix("\r\n").or_else(|| body.strip_prefix('\n')).unwrap_or(body);
+    Ok((metadata, body.to_string()))
+}
@@ -0,0 +1,237 @@
+use super::FileStorageManager;
+use crate::models::{NoteChange, NoteMetadata, OperationRecord};
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const ACTIVITY_DIR_NAME: &str = "activity";
+const ACTIVITY_JOURNAL_NAME: &str = "activity.jsonl";
+// Undo only walks back through what is kept, so older operations are dropped whole
+const MAX_OPERATIONS: usize = 500;
+// Below this size the journal isn't read back after an append
+const TRIM_THRESHOLD_BYTES: u64 = 256 * 1024;
+// Repeated edits of one note this close together are journaled as one operation
+const COALESCE_SECONDS: i64 = 300;
+
+// What is known about the end of a journal without reading it back
+#[derive(Default)]
+struct JournalTail {
+    // Newest record, if this process wrote it
+    last: Option<OperationRecord>,
+    // Size after the last rewrite; appends rewrite again once it has doubled
+    compacted_len: u64,
+}
+
+// Keyed by journal path. Holding the lock also keeps appends and rewrites apart.
+static JOURNALS: Mutex<BTreeMap<PathBuf, JournalTail>> = Mutex::new(BTreeMap::new());
+
+// Note file as read from disk: (file name, front matter, body)
+pub type NoteFile = (String, NoteMetadata, String);
+
+impl FileStorageManager {
+    // The journal holds note bodies for undo, so it stays out of git sync
+    fn activity_journal_path(&self) -> Result<PathBuf> {
+        self.local_file(ACTIVITY_DIR_NAME, ACTIVITY_JOURNAL_NAME)
+    }
+
+    /// Appends an operation to the activity journal. Operations that touched no note are not recorded.
+    pub fn record_operation(&self, operation: &str, summary: String, changes: Vec<NoteChange>) -> Result<Option<OperationRecord>> {
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        let record = OperationRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            operation: operation.to_string(),
+            summary,
+            timestamp: Utc::now(),
+            changes,
+            undoes: None,
+        };
+        self.append_operation(&record)?;
+        Ok(Some(record))
+    }
+
+    /// Like record_operation for a single-note change, but folds it into the
+    /// newest operation when that is the same kind of change to the same note
+    /// made within a few minutes, so autosaves don't flood the feed. The
+    /// journal is only appended to; a folded record is written again under
+    /// its id and replaces the earlier line when read.
+    pub fn record_note_change(&self, operation: &str, summary: String, change: NoteChange) -> Result<()> {
+        let path = self.activity_journal_path()?;
+        let mut journals = JOURNALS.lock().unwrap();
+        let tail = journals.entry(path.clone()).or_default();
+        let now = Utc::now();
+
+        let folded = match tail.last.take() {
+            Some(mut last) if last.operation == operation
+                && last.changes.len() == 1
+                && last.changes[0].note_id == change.note_id
+                && now - last.timestamp < Duration::seconds(COALESCE_SECONDS) =>
+            {
+                let first = &mut last.changes[0];
+                // The body before the first edit; unset if that edit left the body alone
+                if first.content_before.is_none() {
+                    first.content_before = change.content_before.clone();
+                }
+                first.title = change.title.clone();
+                first.file_name = change.file_name.clone();
+                first.after = change.after.clone();
+                last.summary = summary.clone();
+                last.timestamp = now;
+                Some(last)
+            }
+            other => {
+                tail.last = other;
+                None
+            }
+        };
+
+        let record = folded.unwrap_or_else(|| OperationRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            operation: operation.to_string(),
+            summary,
+            timestamp: now,
+            changes: vec![change],
+            undoes: None,
+        });
+        append_record(&path, tail, record)
+    }
+
+    pub fn append_operation(&self, record: &OperationRecord) -> Result<()> {
+        let path = self.activity_journal_path()?;
+        let mut journals = JOURNALS.lock().unwrap();
+        append_record(&path, journals.entry(path.clone()).or_default(), record.clone())
+    }
+
+    /// Drops the saved bodies of an operation that can no longer be undone.
+    pub fn forget_operation_bodies(&self, record_id: &str) -> Result<()> {
+        self.forget_bodies(|record, _| record.id == record_id)
+    }
+
+    /// Drops every saved body of a note, e.g. once it is encrypted.
+    pub fn forget_note_bodies(&self, note_id: &str) -> Result<()> {
+        self.forget_bodies(|_, change| change.note_id == note_id)
+    }
+
+    fn forget_bodies(&self, matches: impl Fn(&OperationRecord, &NoteChange) -> bool) -> Result<()> {
+        let path = self.activity_journal_path()?;
+        let mut journals = JOURNALS.lock().unwrap();
+        let tail = journals.entry(path.clone()).or_default();
+        let mut records = read_records(&path)?;
+        let mut changed = false;
+        for record in records.iter_mut() {
+            for i in 0..record.changes.len() {
+                if matches(record, &record.changes[i]) {
+                    changed |= record.changes[i].content_before.take().is_some();
+                }
+            }
+        }
+        if changed {
+            write_records(&path, tail, &records)?;
+            // The cached copy may hold a body that is gone now
+            if tail.last.is_some() {
+                tail.last = records.last().cloned();
+            }
+        }
+        Ok(())
+    }
+
+    /// Every journaled operation, oldest first.
+    pub fn read_operations(&self) -> Result<Vec<OperationRecord>> {
+        let path = self.activity_journal_path()?;
+        let _journals = JOURNALS.lock().unwrap();
+        read_records(&path)
+    }
+
+    /// Reads a note file by id, including trashed notes.
+    pub fn find_note_file(&self, note_id: &str) -> Result<Option<NoteFile>> {
+        for file_info in self.scan_existing_files()? {
+            if let Ok((metadata, content)) = self.parse_note(&file_info.name) {
+                if metadata.id == note_id {
+                    return Ok(Some((file_info.name, metadata, content)));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn append_record(path: &Path, tail: &mut JournalTail, record: OperationRecord) -> Result<()> {
+    let mut line = serde_json::to_string(&record)?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)
+        .context("Failed to open activity journal")?;
+    file.write_all(line.as_bytes())?;
+    tail.last = Some(record);
+
+    // Folded records leave stale lines behind, so this also compacts them
+    if file.metadata()?.len() > TRIM_THRESHOLD_BYTES.max(2 * tail.compacted_len) {
+        let records = read_records(path)?;
+        write_records(path, tail, &records)?;
+    }
+    Ok(())
+}
+
+fn read_records(path: &Path) -> Result<Vec<OperationRecord>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let text = fs::read_to_string(path).context("Failed to read activity journal")?;
+    let mut records: Vec<OperationRecord> = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        match serde_json::from_str::<OperationRecord>(line) {
+            // A folded record follows the line it replaces
+            Ok(record) if records.last().is_some_and(|last| last.id == record.id) => {
+                *records.last_mut().unwrap() = record;
+            }
+            Ok(record) => records.push(record),
+            Err(e) => log::warn!("Skipping unreadable activity journal line: {}", e),
+        }
+    }
+    Ok(records)
+}
+
+// Rewrites the journal with the newest MAX_OPERATIONS records
+fn write_records(path: &Path, tail: &mut JournalTail, records: &[OperationRecord]) -> Result<()> {
+    let mut text = String::new();
+    for record in &records[records.len().saturating_sub(MAX_OPERATIONS)..] {
+        text.push_str(&serde_json::to_string(record)?);
+        text.push('\n');
+    }
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, &text).context("Failed to write activity journal")?;
+    fs::rename(&temp_path, path).context("Failed to write activity journal")?;
+    tail.compacted_len = text.len() as u64;
+    Ok(())
+}
+
+/// Describes how one note changed; either side is None for created or removed notes.
+pub fn note_change(before: Option<&NoteFile>, after: Option<&NoteFile>) -> NoteChange {
+    let (note_id, title, file_name) = match (after, before) {
+        (Some((name, metadata, _)), _) | (None, Some((name, metadata, _))) => {
+            (metadata.id.clone(), metadata.title.clone(), name.clone())
+        }
+        (None, None) => Default::default(),
+    };
+    let content_before = match (before, after) {
+        (Some((_, _, old)), Some((_, _, new))) if old == new => None,
+        (Some((_, _, old)), _) => Some(old.clone()),
+        (None, _) => None,
+    };
+
+    NoteChange {
+        note_id,
+        title,
+        file_name,
+        before: before.map(|(_, metadata, _)| metadata.clone()),
+        after: after.map(|(_, metadata, _)| metadata.clone()),
+        content_before,
+    }
+}
@@ -2,11 +2,23 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use anyhow::{Result, Context};
 use crate::models::NoteMetadata;
+use crate::vault::VaultSettings;
 use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+mod drafts;
+mod history;
+mod journal;
+
+pub use drafts::DraftEntry;
+pub use history::{parse_note_text, HistoryEntry};
+pub use journal::{note_change, NoteFile};
 
 #[derive(Clone)]
 pub struct FileStorageManager {
     pub notes_directory: PathBuf,
+    // Outside the notes folder, for the draft journal; None keeps it in the vault directory
+    drafts_directory: Option<PathBuf>,
 }
 
 impl FileStorageManager {
@@ -17,9 +29,50 @@ impl FileStorageManager {
         
         Ok(Self {
             notes_directory,
+            drafts_directory: None,
         })
     }
 
+    pub fn with_drafts_directory(mut self, drafts_directory: PathBuf) -> Self {
+        self.drafts_directory = Some(drafts_directory);
+        self
+    }
+
+    pub fn vault_directory(&self) -> PathBuf {
+        crate::vault::vault_directory(&self.notes_directory)
+    }
+    
+    /// A folder in the vault directory for files that stay on this machine. It
+    /// carries its own `.gitignore` so git sync never commits what is inside.
+    pub fn local_directory(&self, name: &str) -> Result<PathBuf> {
+        let dir = self.vault_directory().join(name);
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            fs::write(&gitignore, "*\n")?;
+        }
+        Ok(dir)
+    }
+    
+    // `file_name` inside a local directory, moving in the copy older versions
+    // kept directly in the vault directory
+    pub fn local_file(&self, dir_name: &str, file_name: &str) -> Result<PathBuf> {
+        let path = self.local_directory(dir_name)?.join(file_name);
+        let legacy = self.vault_directory().join(file_name);
+        if legacy.exists() && !path.exists() {
+            fs::rename(&legacy, &path).with_context(|| format!("Failed to move {}", file_name))?;
+        }
+        Ok(path)
+    }
+    
+    pub fn load_vault_settings(&self) -> Result<VaultSettings> {
+        VaultSettings::load(&self.notes_directory)
+    }
+    
+    pub fn save_vault_settings(&self, settings: &VaultSettings) -> Result<()> {
+        settings.save(&self.notes_directory)
+    }
+
     pub fn parse_note(&self, file_name: &str) -> Result<(NoteMetadata, String)> {
         let file_path = self.notes_directory.join(file_name);
         let content = fs::read_to_string(&file_path)?;
@@ -133,6 +186,12 @@ impl FileStorageManager {
             modified: modified_time.to_rfc3339(),
             favorite: false,
             deleted: false,
+            archived: false,
+            locked: false,
+            encrypted: false,
+            remind: None,
+            template: None,
+            properties: BTreeMap::new(),
         };
 
         self.save_note(file_name, &new_metadata, &content)?;
@@ -146,6 +205,17 @@ impl FileStorageManager {
         // Ensure there are newlines around YAML markers
         let full_content = format!("---\n{}---\n{}", yaml, content);
         
+        // Keep the version being replaced; history must never block a save.
+        // Encrypted notes keep no history, which would hold plain or armored bodies.
+        let previous = if metadata.encrypted { None } else { fs::read_to_string(&file_path).ok() };
+        if let Some(previous) = previous {
+            if previous != full_content {
+                if let Err(e) = self.snapshot_note(&metadata.id, &previous, false) {
+                    log::warn!("Failed to snapshot {} before saving: {}", file_name, e);
+                }
+            }
+        }
+        
         // Use atomic write: write to temp file then rename
         let temp_path = file_path.with_extension("tmp");
         fs::write(&temp_path, full_content)?;
@@ -330,6 +400,12 @@ mod tests {
             modified: Utc::now().to_rfc3339(),
             favorite: false,
             deleted: false,
+            archived: false,
+            locked: false,
+            encrypted: false,
+            remind: None,
+            template: None,
+            properties: BTreeMap::new(),
         }
     }
 
@@ -1175,6 +1175,47 @@ impl GitSyncManager {
         Ok(commits)
     }
     
+    // Number of note files changed per local day, walking back at most `max_commits`
+    pub fn get_daily_activity(&self, max_commits: usize) -> Result<std::collections::BTreeMap<String, usize>> {
+        let repo = Repository::open(&self.repo_path)
+            .context("Failed to open repository")?;
+        
+        let mut activity = std::collections::BTreeMap::new();
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        
+        for commit_id in revwalk.take(max_commits) {
+            let commit = repo.find_commit(commit_id?)?;
+            let tree = commit.tree()?;
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree()?),
+                Err(_) => None,
+            };
+            
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+            let changed_notes = diff.deltas()
+                .filter(|delta| {
+                    delta.new_file().path()
+                        .or_else(|| delta.old_file().path())
+                        .and_then(|p| p.extension())
+                        .is_some_and(|ext| ext == "md")
+                })
+                .count();
+            
+            if changed_notes > 0 {
+                let day = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                    .unwrap_or_else(chrono::Utc::now)
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d")
+                    .to_string();
+                *activity.entry(day).or_insert(0) += changed_notes;
+            }
+        }
+        
+        Ok(activity)
+    }
+    
     // 新增：合并远程变更到本地
     fn merge_remote_changes(&self, repo: &Repository, remote_commit: &git2::Commit) -> Result<()> {
         println!("🔄 Starting merge of remote changes...");
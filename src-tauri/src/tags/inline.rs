@@ -0,0 +1,93 @@
+use super::tag_matches;
+use crate::vault::TagPolicy;
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use regex::Regex;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Distinct `#tags` written in the body, in order of first use.
+pub fn extract_inline_tags(content: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for (_, tag) in find_inline_tags(content) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Rewrites `#from` and its nested `#from/...` tags to `to`; None if the body has none.
+/// Tags are compared as the policy normalizes them, like when they are counted.
+pub fn rename_inline_tags(policy: &TagPolicy, content: &str, from: &str, to: &str) -> Option<String> {
+    let matches: Vec<(Range<usize>, String)> = find_inline_tags(content)
+        .into_iter()
+        .map(|(range, tag)| (range, policy.normalize(&tag)))
+        .filter(|(_, tag)| tag_matches(tag, from, true))
+        .collect();
+    if matches.is_empty() {
+        return None;
+    }
+
+    let mut renamed = content.to_string();
+    for (range, tag) in matches.into_iter().rev() {
+        renamed.replace_range(range, &format!("{}{}", to, &tag[from.len()..]));
+    }
+    Some(renamed)
+}
+
+// Byte range of each tag name (after the `#`) in plain paragraph text.
+// Headings, code, links and images are skipped; URLs never match because a
+// tag has to start the text or follow whitespace.
+fn find_inline_tags(content: &str) -> Vec<(Range<usize>, String)> {
+    static HASHTAG: OnceLock<Regex> = OnceLock::new();
+    let hashtag = HASHTAG.get_or_init(|| Regex::new(r"(?:^|\s)#([\w][\w/-]*)").unwrap());
+    let mut found = Vec::new();
+    let mut skip_depth = 0usize;
+
+    for (event, range) in Parser::new_ext(content, Options::empty()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading(..) | Tag::CodeBlock(_) | Tag::Link(..) | Tag::Image(..)) => skip_depth += 1,
+            Event::End(Tag::Heading(..) | Tag::CodeBlock(_) | Tag::Link(..) | Tag::Image(..)) => skip_depth = skip_depth.saturating_sub(1),
+            // Escapes and entities make the event text differ from the source; leave those alone
+            Event::Text(text) if skip_depth == 0 && content.get(range.clone()) == Some(&*text) => {
+                for caps in hashtag.captures_iter(&text) {
+                    let name = caps.get(1).unwrap();
+                    let tag = name.as_str().trim_end_matches(['/', '-']);
+                    if tag.chars().all(|c| c.is_ascii_digit()) {
+                        continue;
+                    }
+                    let start = range.start + name.start();
+                    found.push((start..start + tag.len(), tag.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_tags_skip_code_headings_and_urls() {
+        let content = "# Heading #nope\n\
+            Plan #work/clientA and #ideas, see https://example.com/page#anchor.\n\
+            Issue #42 and `#code` stay out.\n\n\
+            ```\n#fenced\n```\n\n\
+            [#linked](https://example.com) #ideas again\n";
+        assert_eq!(extract_inline_tags(content), vec!["work/clientA", "ideas"]);
+
+        let renamed = rename_inline_tags(&TagPolicy::default(), content, "work", "job").unwrap();
+        assert!(renamed.contains("Plan #job/clientA and #ideas"));
+        assert!(rename_inline_tags(&TagPolicy::default(), content, "code", "x").is_none());
+
+        // Matched the way the tags are counted: NFC, and folded when the policy says so
+        let cafe = "Trip #cafe\u{301} and #Rust/Async";
+        assert_eq!(rename_inline_tags(&TagPolicy::default(), cafe, "caf\u{e9}", "coffee").unwrap(), "Trip #coffee and #Rust/Async");
+        assert!(rename_inline_tags(&TagPolicy::default(), cafe, "rust", "lang").is_none());
+        let folded = TagPolicy { case_fold: true, ..TagPolicy::default() };
+        assert_eq!(rename_inline_tags(&folded, cafe, "rust", "lang").unwrap(), "Trip #cafe\u{301} and #lang/async");
+    }
+}
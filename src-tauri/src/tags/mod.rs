@@ -1,7 +1,15 @@
-use crate::storage::FileStorageManager;
-use crate::models::Tag;
+use crate::storage::{note_change, FileStorageManager};
+use crate::models::{BulkResult, NoteChange, NoteMetadata, Tag, TagCleanup, TagNormalization, TagRename, UpdateTagRequest};
+use crate::notes::NoteLockedError;
+use crate::vault::{InlineTagMode, TagDefinition, TagPolicy, VaultSettings};
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+mod inline;
+mod rules;
+mod suggest;
+
+pub use inline::{extract_inline_tags, rename_inline_tags};
 
 pub struct TagsManager {
     storage: FileStorageManager,
@@ -12,74 +20,332 @@ impl TagsManager {
         Self { storage }
     }
     
+    /// Root tags with their nested children. `total_count` includes notes
+    /// tagged with any descendant.
     pub async fn get_all_tags(&self) -> Result<Vec<Tag>> {
-        let files = self.storage.scan_existing_files()?;
-        let mut tag_counts: HashMap<String, usize> = HashMap::new();
+        let tags = self.list_tags()?;
+        Ok(build_tree(&tags, None))
+    }
+    
+    // Every tag and implied parent tag in use by active or archived notes, or
+    // registered, as a flat list sorted by name
+    fn list_tags(&self) -> Result<Vec<Tag>> {
+        let settings = self.storage.load_vault_settings()?;
+        let mut counts: HashMap<String, TagCounts> = HashMap::new();
         
-        for file in files {
-             if let Ok((metadata, _)) = self.storage.parse_note(&file.name) {
-                 if !metadata.deleted {
-                     for tag in metadata.tags {
-                         *tag_counts.entry(tag).or_insert(0) += 1;
+        for file in self.storage.scan_existing_files()? {
+             if let Ok((metadata, content)) = self.storage.parse_note(&file.name) {
+                 let tags: BTreeSet<String> = note_tags(&settings.tag_policy, &metadata, &content).into_iter().collect();
+                 let mut with_parents = BTreeSet::new();
+                 for tag in &tags {
+                     with_parents.extend(tag_and_parents(tag));
+                 }
+                 for tag in with_parents {
+                     let entry = counts.entry(tag.to_string()).or_default();
+                     if !metadata.deleted && !metadata.archived {
+                         entry.total += 1;
+                     }
+                     // Parents are listed whenever a child is
+                     entry.listed |= !metadata.deleted;
+                 }
+                 for tag in tags {
+                     let entry = counts.entry(tag).or_default();
+                     match (metadata.deleted, metadata.archived) {
+                         (true, _) => entry.trashed += 1,
+                         (false, true) => entry.archived += 1,
+                         (false, false) => entry.active += 1,
                      }
                  }
              }
         }
         
-        let mut tags: Vec<Tag> = tag_counts.into_iter().map(|(name, count)| Tag {
-            id: name.clone(),
-            name,
-            note_count: count,
-        }).collect();
+        for name in settings.tags.keys() {
+            for tag in tag_and_parents(name) {
+                counts.entry(tag.to_string()).or_default().listed = true;
+            }
+        }
+        
+        let mut tags: Vec<Tag> = counts.into_iter()
+            .filter(|(_, c)| c.listed)
+            .map(|(name, c)| Tag {
+                total_count: c.total,
+                archived_count: c.archived,
+                trashed_count: c.trashed,
+                ..tag_from_registry(&settings, name, c.active)
+            })
+            .collect();
         
         tags.sort_by(|a, b| a.name.cmp(&b.name));
         
         Ok(tags)
     }
     
+    /// Registers a tag so it is listed even while no note uses it.
     pub async fn create_tag(&self, name: &str) -> Result<Tag> {
-        Ok(Tag {
-            id: name.to_string(),
-            name: name.to_string(),
-            note_count: 0
-        })
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Tag name cannot be empty"));
+        }
+        
+        let mut settings = self.storage.load_vault_settings()?;
+        if !settings.tags.contains_key(name) {
+            settings.tags.insert(name.to_string(), TagDefinition::default());
+            self.storage.save_vault_settings(&settings)?;
+        }
+        
+        self.tag_with_counts(name.to_string())
     }
     
-    pub async fn rename_tag(&self, tag_id: &str, new_name: &str) -> Result<Option<Tag>> {
+    /// Sets a tag's color, icon or description, registering it if needed.
+    pub async fn update_tag(&self, request: UpdateTagRequest) -> Result<Tag> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let name = settings.tag_name(&request.id);
+        let definition = settings.tags.entry(name.clone()).or_default();
+        
+        for (field, value) in [
+            (&mut definition.color, request.color),
+            (&mut definition.icon, request.icon),
+            (&mut definition.description, request.description),
+        ] {
+            if let Some(value) = value {
+                *field = Some(value).filter(|v| !v.trim().is_empty());
+            }
+        }
+        self.storage.save_vault_settings(&settings)?;
+        
+        self.tag_with_counts(name)
+    }
+    
+    /// Renames or moves a tag along with all of its descendants, including
+    /// inline `#tags` unless they are ignored. Locked notes keep the old tags
+    /// and are listed in `skipped_locked`.
+    pub async fn rename_tag(&self, tag_id: &str, new_name: &str) -> Result<BulkResult> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let tag_id = settings.tag_name(tag_id);
+        let tag_id = tag_id.as_str();
+        let new_name = stored_tag_name(&settings.tag_policy, new_name)?;
+        let new_name = new_name.as_str();
+        if tag_matches(new_name, tag_id, true) && new_name != tag_id {
+            return Err(anyhow::anyhow!("Cannot move tag '{}' under itself", tag_id));
+        }
         let files = self.storage.scan_existing_files()?;
-        let mut updated = false;
+        let mut result = BulkResult::default();
+        let mut changes = Vec::new();
         
         for file in files {
              if let Ok((mut metadata, content)) = self.storage.parse_note(&file.name) {
-                 if metadata.tags.contains(&tag_id.to_string()) {
-                     metadata.tags = metadata.tags.into_iter().map(|t| if t == tag_id { new_name.to_string() } else { t }).collect();
-                     self.storage.save_note(&file.name, &metadata, &content)?;
-                     updated = true;
+                 let inline = if settings.tag_policy.inline_tags != InlineTagMode::Ignore && !metadata.encrypted {
+                     rename_inline_tags(&settings.tag_policy, &content, tag_id, new_name)
+                 } else {
+                     None
+                 };
+                 if inline.is_some() || metadata.tags.iter().any(|t| tag_matches(t, tag_id, true)) {
+                     if metadata.locked {
+                         result.skipped_locked.push(metadata.id);
+                         continue;
+                     }
+                     let new_content = inline.unwrap_or_else(|| content.clone());
+                     let before = (file.name.clone(), metadata.clone(), content);
+                     let mut renamed: Vec<String> = Vec::new();
+                     for tag in metadata.tags.drain(..) {
+                         let tag = rename_descendant(&tag, tag_id, new_name).unwrap_or(tag);
+                         if !renamed.contains(&tag) {
+                             renamed.push(tag);
+                         }
+                     }
+                     metadata.tags = renamed;
+                     self.storage.save_note(&file.name, &metadata, &new_content)?;
+                     result.updated.push(metadata.id.clone());
+                     changes.push(note_change(Some(&before), Some(&(file.name, metadata, new_content))));
                  }
              }
         }
         
-        if updated {
-            Ok(Some(Tag { id: new_name.to_string(), name: new_name.to_string(), note_count: 0 }))
-        } else {
-            Ok(None)
+        self.storage.record_operation("rename_tag", format!("Renamed tag '{}' to '{}'", tag_id, new_name), changes)?;
+        
+        // Registry entries follow the tags unless notes still carry the old names
+        let moved: Vec<String> = settings.tags.keys().filter(|name| tag_matches(name, tag_id, true)).cloned().collect();
+        if result.skipped_locked.is_empty() && !moved.is_empty() {
+            for name in moved {
+                if let (Some(definition), Some(renamed)) = (settings.tags.remove(&name), rename_descendant(&name, tag_id, new_name)) {
+                    settings.tags.entry(renamed).or_insert(definition);
+                }
+            }
+            self.storage.save_vault_settings(&settings)?;
         }
+        Ok(result)
     }
     
-    pub async fn delete_tag(&self, tag_id: &str) -> Result<bool> {
+    pub async fn delete_tag(&self, tag_id: &str) -> Result<BulkResult> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let tag_id = settings.tag_name(tag_id);
+        let tag_id = tag_id.as_str();
         let files = self.storage.scan_existing_files()?;
-        let mut deleted = false;
+        let mut result = BulkResult::default();
+        let mut changes = Vec::new();
         
         for file in files {
              if let Ok((mut metadata, content)) = self.storage.parse_note(&file.name) {
                  if metadata.tags.contains(&tag_id.to_string()) {
+                     if metadata.locked {
+                         result.skipped_locked.push(metadata.id);
+                         continue;
+                     }
+                     let before = (file.name.clone(), metadata.clone(), content);
                      metadata.tags.retain(|t| t != tag_id);
-                     self.storage.save_note(&file.name, &metadata, &content)?;
-                     deleted = true;
+                     self.storage.save_note(&file.name, &metadata, &before.2)?;
+                     result.updated.push(metadata.id.clone());
+                     changes.push(note_change(Some(&before), Some(&(file.name, metadata, before.2.clone()))));
                  }
              }
         }
-        Ok(deleted)
+        
+        self.storage.record_operation("delete_tag", format!("Deleted tag '{}'", tag_id), changes)?;
+        
+        if result.skipped_locked.is_empty() && settings.tags.remove(tag_id).is_some() {
+            self.storage.save_vault_settings(&settings)?;
+        }
+        Ok(result)
+    }
+    
+    /// Replaces every source tag with `target` on all notes and drops the
+    /// sources from the registry.
+    pub async fn merge_tags(&self, sources: &[String], target: &str) -> Result<BulkResult> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let target = stored_tag_name(&settings.tag_policy, &settings.tag_name(target.trim()))?;
+        let sources: Vec<String> = sources.iter()
+            .map(|s| settings.tag_name(s))
+            .filter(|s| *s != target)
+            .collect();
+        
+        let (result, changes) = self.rewrite_tags(false, |metadata, _| {
+            let tags = &metadata.tags;
+            if !tags.iter().any(|t| sources.contains(t)) {
+                return None;
+            }
+            let mut merged: Vec<String> = Vec::new();
+            for tag in tags {
+                let tag = if sources.contains(tag) { target.clone() } else { tag.clone() };
+                if !merged.contains(&tag) {
+                    merged.push(tag);
+                }
+            }
+            Some(merged)
+        })?;
+        self.storage.record_operation("merge_tags", format!("Merged {} tags into '{}'", sources.len(), target), changes)?;
+        
+        if result.skipped_locked.is_empty() {
+            for source in &sources {
+                if let Some(definition) = settings.tags.remove(source) {
+                    settings.tags.entry(target.clone()).or_insert(definition);
+                }
+            }
+            self.storage.save_vault_settings(&settings)?;
+        }
+        Ok(result)
+    }
+    
+    pub async fn get_tag_policy(&self) -> Result<TagPolicy> {
+        Ok(self.storage.load_vault_settings()?.tag_policy)
+    }
+    
+    pub async fn set_tag_policy(&self, policy: TagPolicy) -> Result<()> {
+        let mut settings = self.storage.load_vault_settings()?;
+        settings.tag_policy = policy;
+        self.storage.save_vault_settings(&settings)
+    }
+    
+    /// Applies the vault's tag policy to every note, trashed and archived ones
+    /// included. With `dry_run` nothing is written and the result is a preview.
+    pub async fn normalize_tags(&self, dry_run: bool) -> Result<TagNormalization> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let policy = settings.tag_policy.clone();
+        
+        let mut renames: BTreeMap<String, TagRename> = BTreeMap::new();
+        let (result, changes) = self.rewrite_tags(dry_run, |metadata, _| {
+            let tags = &metadata.tags;
+            let normalized = policy.normalize_all(tags.iter().cloned());
+            if &normalized == tags {
+                return None;
+            }
+            for tag in tags {
+                let to = policy.normalize(tag);
+                if &to != tag {
+                    renames.entry(tag.clone())
+                        .or_insert_with(|| TagRename { from: tag.clone(), to, note_count: 0 })
+                        .note_count += 1;
+                }
+            }
+            Some(normalized)
+        })?;
+        
+        if !dry_run {
+            self.storage.record_operation("normalize_tags", format!("Normalized tags on {} notes", changes.len()), changes)?;
+            let names: Vec<String> = settings.tags.keys().cloned().collect();
+            for name in names {
+                let normalized = policy.normalize(&name);
+                if normalized != name {
+                    if let Some(definition) = settings.tags.remove(&name) {
+                        settings.tags.entry(normalized).or_insert(definition);
+                    }
+                }
+            }
+            self.storage.save_vault_settings(&settings)?;
+        }
+        
+        Ok(TagNormalization {
+            renames: renames.into_values().collect(),
+            updated: result.updated,
+            skipped_locked: result.skipped_locked,
+            applied: !dry_run,
+        })
+    }
+    
+    /// Copies inline `#tags` into front matter on every note that is missing some.
+    pub async fn sync_inline_tags(&self) -> Result<BulkResult> {
+        let policy = self.storage.load_vault_settings()?.tag_policy;
+        let (result, changes) = self.rewrite_tags(false, |metadata, content| {
+            if metadata.encrypted {
+                return None;
+            }
+            let inline = policy.normalize_all(extract_inline_tags(content));
+            if inline.iter().all(|t| metadata.tags.contains(t)) {
+                return None;
+            }
+            Some(policy.normalize_all(metadata.tags.iter().cloned().chain(inline)))
+        })?;
+        self.storage.record_operation("sync_inline_tags", format!("Copied inline tags into {} notes", changes.len()), changes)?;
+        Ok(result)
+    }
+    
+    // Runs `rewrite` over the tags of every note and saves the ones it returns
+    // new tags for. Locked notes are reported, not changed.
+    fn rewrite_tags<F: FnMut(&NoteMetadata, &str) -> Option<Vec<String>>>(&self, dry_run: bool, mut rewrite: F) -> Result<(BulkResult, Vec<NoteChange>)> {
+        let mut result = BulkResult::default();
+        let mut changes = Vec::new();
+        
+        for file in self.storage.scan_existing_files()? {
+            if let Ok((mut metadata, content)) = self.storage.parse_note(&file.name) {
+                let tags = match rewrite(&metadata, &content) {
+                    Some(tags) => tags,
+                    None => continue,
+                };
+                if metadata.locked {
+                    result.skipped_locked.push(metadata.id);
+                    continue;
+                }
+                result.updated.push(metadata.id.clone());
+                if dry_run {
+                    continue;
+                }
+                
+                let before = (file.name.clone(), metadata.clone(), content);
+                metadata.tags = tags;
+                self.storage.save_note(&file.name, &metadata, &before.2)?;
+                changes.push(note_change(Some(&before), Some(&(file.name, metadata, before.2.clone()))));
+            }
+        }
+        Ok((result, changes))
     }
     
     pub async fn add_tag_to_note(&self, note_id: &str, tag_name: &str) -> Result<Tag> {
@@ -87,11 +353,20 @@ impl TagsManager {
         for file in files {
              if let Ok((mut metadata, content)) = self.storage.parse_note(&file.name) {
                  if metadata.id == note_id {
-                     if !metadata.tags.contains(&tag_name.to_string()) {
-                         metadata.tags.push(tag_name.to_string());
+                     if metadata.locked {
+                         return Err(NoteLockedError::new(note_id).into());
+                     }
+                     let settings = self.storage.load_vault_settings()?;
+                     let tag_name = stored_tag_name(&settings.tag_policy, tag_name)?;
+                     if !metadata.tags.contains(&tag_name) {
+                         let before = (file.name.clone(), metadata.clone(), content.clone());
+                         metadata.tags.push(tag_name.clone());
                          self.storage.save_note(&file.name, &metadata, &content)?;
+                         let summary = format!("Tagged '{}' with '{}'", metadata.title, tag_name);
+                         let change = note_change(Some(&before), Some(&(file.name, metadata, content)));
+                         self.storage.record_note_change("add_tag", summary, change)?;
                      }
-                     return Ok(Tag { id: tag_name.to_string(), name: tag_name.to_string(), note_count: 1 });
+                     return self.tag_with_counts(tag_name);
                  }
              }
         }
@@ -103,9 +378,16 @@ impl TagsManager {
         for file in files {
              if let Ok((mut metadata, content)) = self.storage.parse_note(&file.name) {
                  if metadata.id == note_id {
+                     if metadata.locked {
+                         return Err(NoteLockedError::new(note_id).into());
+                     }
                      if metadata.tags.contains(&tag_name.to_string()) {
+                         let before = (file.name.clone(), metadata.clone(), content.clone());
                          metadata.tags.retain(|t| t != tag_name);
                          self.storage.save_note(&file.name, &metadata, &content)?;
+                         let summary = format!("Removed tag '{}' from '{}'", tag_name, metadata.title);
+                         let change = note_change(Some(&before), Some(&(file.name, metadata, content)));
+                         self.storage.record_note_change("remove_tag", summary, change)?;
                          return Ok(true);
                      }
                      return Ok(false);
@@ -120,23 +402,376 @@ impl TagsManager {
         for file in files {
              if let Ok((metadata, _)) = self.storage.parse_note(&file.name) {
                  if metadata.id == note_id {
-                     return Ok(metadata.tags.into_iter().map(|t| Tag {
-                         id: t.clone(),
-                         name: t,
-                         note_count: 1
-                     }).collect());
+                     return self.tags_with_counts(metadata.tags);
                  }
              }
         }
         Ok(vec![])
     }
     
-    pub async fn cleanup_unused_tags(&self) -> Result<usize> {
-        Ok(0)
+    /// Drops registry entries, tag pins and manual orders for tags no active or archived note
+    /// uses. With `dry_run` the tags are only listed.
+    pub async fn cleanup_unused_tags(&self, dry_run: bool) -> Result<TagCleanup> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let mut used: HashSet<String> = HashSet::new();
+        for file in self.storage.scan_existing_files()? {
+            if let Ok((metadata, content)) = self.storage.parse_note(&file.name) {
+                if !metadata.deleted {
+                    used.extend(note_tags(&settings.tag_policy, &metadata, &content));
+                }
+            }
+        }
+        // A parent stays while any tag below it is in use
+        let in_use = |name: &String| used.iter().any(|t| tag_matches(t, name, true));
+        
+        let mut removed: Vec<String> = settings.tags.keys()
+            .chain(settings.pins.tags.keys())
+            .chain(settings.order.tags.keys())
+            .filter(|name| !in_use(name))
+            .cloned()
+            .collect();
+        removed.sort();
+        removed.dedup();
+        
+        if !dry_run && !removed.is_empty() {
+            settings.tags.retain(|name, _| !removed.contains(name));
+            settings.pins.tags.retain(|name, _| !removed.contains(name));
+            settings.order.tags.retain(|name, _| !removed.contains(name));
+            self.storage.save_vault_settings(&settings)?;
+        }
+        Ok(TagCleanup { tags: removed, applied: !dry_run })
+    }
+    
+    fn tag_with_counts(&self, name: String) -> Result<Tag> {
+        Ok(self.tags_with_counts(vec![name])?.remove(0))
+    }
+    
+    // Counts come from a single scan of the vault, however many tags are asked for
+    fn tags_with_counts(&self, names: Vec<String>) -> Result<Vec<Tag>> {
+        let listed: HashMap<String, Tag> = self.list_tags()?.into_iter().map(|t| (t.name.clone(), t)).collect();
+        let settings = self.storage.load_vault_settings()?;
+        Ok(names.into_iter().map(|name| match listed.get(&name) {
+            Some(tag) => tag.clone(),
+            None => tag_from_registry(&settings, name, 0),
+        }).collect())
     }
     
     pub async fn search_tags(&self, query: &str) -> Result<Vec<Tag>> {
-         let all_tags = self.get_all_tags().await?;
+         let all_tags = self.list_tags()?;
          Ok(all_tags.into_iter().filter(|t| t.name.to_lowercase().contains(&query.to_lowercase())).collect())
     }
 }
+
+pub const TAG_SEPARATOR: char = '/';
+
+// Notes carrying one tag, by state
+#[derive(Default)]
+struct TagCounts {
+    active: usize,
+    archived: usize,
+    trashed: usize,
+    // Active notes with the tag or a tag below it
+    total: usize,
+    listed: bool,
+}
+
+/// Which tag rules a save lets add tags.
+pub enum RuleTags<'a> {
+    /// Every matching rule, for new notes.
+    All,
+    /// Only rules the edit starts matching, given the note before the edit.
+    NewMatches(&'a NoteMetadata, Option<&'a str>),
+    /// None, the caller's tag list is final.
+    Skip,
+}
+
+/// Tags to write to front matter when `metadata` is saved: normalized, with
+/// tags from matching rules and, in sync mode, the body's inline tags added.
+/// Pass no content for encrypted notes.
+pub fn tags_for_save(settings: &VaultSettings, metadata: &NoteMetadata, tags: Vec<String>, content: Option<&str>, rule_tags: RuleTags) -> Vec<String> {
+    let inline = match content {
+        Some(content) if settings.tag_policy.inline_tags == InlineTagMode::Sync => inline_tags(&settings.tag_policy, content),
+        _ => vec![],
+    };
+    let rule_set = rules::RuleSet::new(&settings.tag_rules);
+    let from_rules = match rule_tags {
+        RuleTags::All => rule_set.tags_for(metadata, content),
+        RuleTags::NewMatches(previous, previous_content) => {
+            let matched_before = rule_set.tags_for(previous, previous_content);
+            rule_set.tags_for(metadata, content).into_iter().filter(|t| !matched_before.contains(t)).collect()
+        }
+        RuleTags::Skip => vec![],
+    };
+    settings.tag_policy.normalize_all(tags.into_iter().chain(inline).chain(from_rules))
+}
+
+/// Inline `#tags` of a body, normalized like front matter tags. The body is
+/// not parsed at all when the policy ignores inline tags.
+pub fn inline_tags(policy: &TagPolicy, content: &str) -> Vec<String> {
+    if policy.inline_tags == InlineTagMode::Ignore {
+        return vec![];
+    }
+    policy.normalize_all(extract_inline_tags(content))
+}
+
+/// Front matter tags plus, unless the policy ignores them, inline `#tags`.
+/// Encrypted bodies are never read for tags.
+pub fn note_tags(policy: &TagPolicy, metadata: &NoteMetadata, content: &str) -> Vec<String> {
+    let mut tags = metadata.tags.clone();
+    if !metadata.encrypted {
+        for tag in inline_tags(policy, content) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    tags
+}
+
+/// Whether `tag` is `filter` or, with `include_descendants`, nested below it.
+pub fn tag_matches(tag: &str, filter: &str, include_descendants: bool) -> bool {
+    tag == filter || (include_descendants && tag.strip_prefix(filter).is_some_and(|rest| rest.starts_with(TAG_SEPARATOR)))
+}
+
+// `name` as notes store it, refusing names that are empty or have an empty level
+fn stored_tag_name(policy: &TagPolicy, name: &str) -> Result<String> {
+    let name = policy.normalize(name);
+    if name.is_empty() || name.split(TAG_SEPARATOR).any(|level| level.trim().is_empty()) {
+        return Err(anyhow::anyhow!("Tag name cannot be empty"));
+    }
+    Ok(name)
+}
+
+fn parent_tag(tag: &str) -> Option<&str> {
+    tag.rsplit_once(TAG_SEPARATOR).map(|(parent, _)| parent)
+}
+
+// "a/b/c" -> ["a", "a/b", "a/b/c"]
+fn tag_and_parents(tag: &str) -> impl Iterator<Item = &str> {
+    tag.match_indices(TAG_SEPARATOR)
+        .map(move |(i, _)| &tag[..i])
+        .chain(std::iter::once(tag))
+}
+
+// New name for `tag` when `from` (and everything under it) becomes `to`
+fn rename_descendant(tag: &str, from: &str, to: &str) -> Option<String> {
+    if tag_matches(tag, from, true) {
+        Some(format!("{}{}", to, &tag[from.len()..]))
+    } else {
+        None
+    }
+}
+
+fn build_tree(tags: &[Tag], parent: Option<&str>) -> Vec<Tag> {
+    tags.iter()
+        .filter(|t| parent_tag(&t.name) == parent)
+        .map(|t| Tag { children: build_tree(tags, Some(&t.name)), ..t.clone() })
+        .collect()
+}
+
+fn tag_from_registry(settings: &VaultSettings, name: String, note_count: usize) -> Tag {
+    match settings.tags.get(&name) {
+        Some(definition) => Tag {
+            id: definition.id.clone(),
+            name,
+            note_count,
+            total_count: note_count,
+            archived_count: 0,
+            trashed_count: 0,
+            color: definition.color.clone(),
+            icon: definition.icon.clone(),
+            description: definition.description.clone(),
+            created_at: Some(definition.created),
+            children: vec![],
+        },
+        None => Tag {
+            id: name.clone(),
+            name,
+            note_count,
+            total_count: note_count,
+            archived_count: 0,
+            trashed_count: 0,
+            color: None,
+            icon: None,
+            description: None,
+            created_at: None,
+            children: vec![],
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+
+    #[tokio::test]
+    async fn test_registered_tags_persist_and_follow_renames() {
+        let vault = test_vault();
+        let manager = vault.tags();
+
+        let created = manager.create_tag(" ideas ").await.unwrap();
+        assert_eq!((created.name.as_str(), created.note_count), ("ideas", 0));
+        assert_ne!(created.id, "ideas");
+
+        manager.update_tag(UpdateTagRequest {
+            id: created.id.clone(),
+            color: Some("#ff8800".to_string()),
+            icon: None,
+            description: Some("Half-baked".to_string()),
+        }).await.unwrap();
+
+        vault.create_note("Rockets", None, &["ideas", "space"]).await;
+
+        let tags = manager.get_all_tags().await.unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!((tags[0].id.as_str(), tags[0].note_count), (created.id.as_str(), 1));
+        assert_eq!(tags[0].color.as_deref(), Some("#ff8800"));
+        assert_eq!(tags[1].id, "space");
+
+        manager.rename_tag(&created.id, "someday").await.unwrap();
+        let renamed = manager.get_all_tags().await.unwrap().into_iter().find(|t| t.name == "someday").unwrap();
+        assert_eq!((renamed.id, renamed.note_count), (created.id, 1));
+        assert_eq!(renamed.description.as_deref(), Some("Half-baked"));
+    }
+
+    #[tokio::test]
+    async fn test_nested_tags_roll_up_and_move_together() {
+        let vault = test_vault();
+        let notes = &vault.notes;
+        let manager = vault.tags();
+
+        for (title, tags) in [("Kickoff", vec!["work/clientA/meetings", "work/clientA"]), ("Invoice", vec!["work/clientB"])] {
+            vault.create_note(title, None, &tags).await;
+        }
+
+        let tree = manager.get_all_tags().await.unwrap();
+        assert_eq!(tree.len(), 1);
+        assert_eq!((tree[0].name.as_str(), tree[0].note_count, tree[0].total_count), ("work", 0, 2));
+        let client_a = &tree[0].children[0];
+        assert_eq!((client_a.note_count, client_a.total_count), (1, 1));
+        assert_eq!(client_a.children[0].name, "work/clientA/meetings");
+
+        assert!(notes.get_notes_by_tag("work", false).await.unwrap().is_empty());
+        assert_eq!(notes.get_notes_by_tag("work", true).await.unwrap().len(), 2);
+        assert!(manager.rename_tag("work", "work/old").await.is_err());
+
+        manager.rename_tag("work/clientA", "clients/a").await.unwrap();
+        let moved = notes.get_notes_by_tag("clients", true).await.unwrap();
+        assert_eq!(moved[0].tags, vec!["clients/a/meetings".to_string(), "clients/a".to_string()]);
+        assert_eq!(notes.get_notes_by_tag("work", true).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_merge_and_normalize_tags() {
+        let vault = test_vault();
+        let notes = &vault.notes;
+        let manager = vault.tags();
+
+        // Written before case folding is switched on
+        let mut ids = Vec::new();
+        for tags in [vec!["Rust", "cafe\u{301}"], vec!["rust", "rust "], vec!["RUST", "lang"]] {
+            let note = vault.create_note("Note", None, &tags).await;
+            ids.push(note.id);
+        }
+        // Trimming and NFC apply on save by default
+        let first = notes.get_note(&ids[0]).await.unwrap().unwrap();
+        assert_eq!(first.tags, vec!["Rust".to_string(), "caf\u{e9}".to_string()]);
+        assert_eq!(notes.get_note(&ids[1]).await.unwrap().unwrap().tags, vec!["rust".to_string()]);
+
+        let merged = manager.merge_tags(&["RUST".to_string()], "Rust").await.unwrap();
+        assert_eq!(merged.updated, vec![ids[2].clone()]);
+        assert_eq!(notes.get_note(&ids[2]).await.unwrap().unwrap().tags, vec!["Rust".to_string(), "lang".to_string()]);
+
+        manager.set_tag_policy(TagPolicy { case_fold: true, ..TagPolicy::default() }).await.unwrap();
+        let preview = manager.normalize_tags(true).await.unwrap();
+        assert!(!preview.applied);
+        assert_eq!(preview.renames.len(), 1);
+        assert_eq!((preview.renames[0].from.as_str(), preview.renames[0].note_count), ("Rust", 2));
+        assert_eq!(notes.get_note(&ids[0]).await.unwrap().unwrap().tags[0], "Rust");
+
+        let applied = manager.normalize_tags(false).await.unwrap();
+        assert_eq!(applied.updated.len(), 2);
+        let names: Vec<String> = manager.search_tags("").await.unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["caf\u{e9}", "lang", "rust"]);
+
+        let tag = manager.add_tag_to_note(&ids[2], " LANG ").await.unwrap();
+        assert_eq!(tag.name, "lang");
+
+        // New names are stored the way the policy writes them
+        manager.rename_tag("lang", "Languages").await.unwrap();
+        assert_eq!(notes.get_note(&ids[2]).await.unwrap().unwrap().tags, vec!["rust".to_string(), "languages".to_string()]);
+        manager.merge_tags(&["languages".to_string()], "Rust").await.unwrap();
+        assert_eq!(notes.get_note(&ids[2]).await.unwrap().unwrap().tags, vec!["rust".to_string()]);
+        assert!(manager.normalize_tags(true).await.unwrap().renames.is_empty());
+        for empty in ["", " ", "job/", "/job"] {
+            assert!(manager.rename_tag("rust", empty).await.is_err());
+        }
+        assert!(manager.merge_tags(&["rust".to_string()], "").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_inline_tags_count_rename_and_sync() {
+        let vault = test_vault();
+        let notes = &vault.notes;
+        let manager = vault.tags();
+
+        let note = vault.create_note("Imported", Some("Call about #work/clientA today\n"), &[]).await;
+        assert!(note.tags.is_empty());
+        assert_eq!(note.inline_tags, vec!["work/clientA".to_string()]);
+
+        assert_eq!(manager.get_all_tags().await.unwrap()[0].total_count, 1);
+        assert_eq!(notes.get_notes_by_tag("work", true).await.unwrap().len(), 1);
+        assert!(notes.get_untagged_notes().await.unwrap().is_empty());
+
+        manager.rename_tag("work", "job").await.unwrap();
+        let renamed = notes.get_note(&note.id).await.unwrap().unwrap();
+        assert_eq!(renamed.content, "Call about #job/clientA today\n");
+
+        manager.set_tag_policy(TagPolicy { inline_tags: InlineTagMode::Sync, ..TagPolicy::default() }).await.unwrap();
+        assert_eq!(manager.sync_inline_tags().await.unwrap().updated, vec![note.id.clone()]);
+        assert_eq!(notes.get_note(&note.id).await.unwrap().unwrap().tags, vec!["job/clientA".to_string()]);
+
+        manager.set_tag_policy(TagPolicy { inline_tags: InlineTagMode::Ignore, ..TagPolicy::default() }).await.unwrap();
+        vault.create_note("Plain", Some("#ignored"), &[]).await;
+        assert!(manager.search_tags("ignored").await.unwrap().is_empty());
+        assert!(notes.get_note(&note.id).await.unwrap().unwrap().inline_tags.is_empty());
+
+        manager.set_tag_policy(TagPolicy { case_fold: true, ..TagPolicy::default() }).await.unwrap();
+        vault.create_note("Lang", Some("Notes on #Rust"), &["rust"]).await;
+        let rust: Vec<_> = manager.get_all_tags().await.unwrap().into_iter().filter(|t| t.name.eq_ignore_ascii_case("rust")).collect();
+        assert_eq!(rust.len(), 1);
+        assert_eq!(rust[0].name, "rust");
+    }
+
+    #[tokio::test]
+    async fn test_tag_stats_and_cleanup() {
+        let vault = test_vault();
+        let notes = &vault.notes;
+        let manager = vault.tags();
+
+        let mut ids = Vec::new();
+        for tags in [vec!["work", "old"], vec!["work"], vec!["work/archive"]] {
+            let note = vault.create_note("Note", None, &tags).await;
+            ids.push(note.id);
+        }
+        notes.pin_note(&ids[0], Some("old")).await.unwrap();
+        notes.archive_note(&ids[1]).await.unwrap();
+        notes.delete_note(&ids[0]).await.unwrap();
+        manager.create_tag("someday").await.unwrap();
+
+        let work = manager.add_tag_to_note(&ids[2], "work").await.unwrap();
+        assert_eq!((work.note_count, work.archived_count, work.trashed_count, work.total_count), (1, 1, 1, 1));
+        // Only in the trash
+        assert!(manager.search_tags("old").await.unwrap().is_empty());
+
+        let preview = manager.cleanup_unused_tags(true).await.unwrap();
+        assert_eq!((preview.tags.clone(), preview.applied), (vec!["old".to_string(), "someday".to_string()], false));
+        assert_eq!(manager.search_tags("someday").await.unwrap().len(), 1);
+
+        manager.cleanup_unused_tags(false).await.unwrap();
+        let settings = manager.storage.load_vault_settings().unwrap();
+        assert!(settings.tags.is_empty() && settings.pins.tags.is_empty());
+        assert!(manager.cleanup_unused_tags(true).await.unwrap().tags.is_empty());
+    }
+}
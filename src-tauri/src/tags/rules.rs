@@ -0,0 +1,224 @@
+use super::TagsManager;
+use crate::models::{BulkResult, NoteMetadata, TagRulePreview};
+use crate::vault::{RuleCondition, TagRule};
+use anyhow::Result;
+use regex::Regex;
+
+// Rules with their patterns compiled once per batch of notes
+pub(super) struct RuleSet<'a> {
+    rules: Vec<(&'a TagRule, Option<Regex>)>,
+}
+
+impl<'a> RuleSet<'a> {
+    pub fn new(rules: &'a [TagRule]) -> Self {
+        let rules = rules.iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| match compile(&rule.condition) {
+                Ok(regex) => Some((rule, regex)),
+                Err(e) => {
+                    log::warn!("Skipping tag rule '{}': {}", rule.name, e);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Tags the rules add to a note. Body rules never see encrypted notes.
+    pub fn tags_for(&self, metadata: &NoteMetadata, content: Option<&str>) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for (rule, regex) in &self.rules {
+            let matched = match (&rule.condition, regex) {
+                (RuleCondition::TitleMatches { .. }, Some(regex)) => regex.is_match(&metadata.title),
+                (RuleCondition::BodyMatches { .. }, Some(regex)) => content.is_some_and(|c| regex.is_match(c)),
+                (RuleCondition::FromTemplate { template }, _) => metadata.template.as_deref() == Some(template.as_str()),
+                _ => false,
+            };
+            if matched {
+                for tag in &rule.tags {
+                    if !tags.contains(tag) {
+                        tags.push(tag.clone());
+                    }
+                }
+            }
+        }
+        tags
+    }
+}
+
+fn compile(condition: &RuleCondition) -> Result<Option<Regex>> {
+    match condition {
+        RuleCondition::TitleMatches { pattern } | RuleCondition::BodyMatches { pattern } => {
+            Regex::new(pattern)
+                .map(Some)
+                .map_err(|e| anyhow::anyhow!("Invalid pattern '{}': {}", pattern, e))
+        }
+        RuleCondition::FromTemplate { .. } => Ok(None),
+    }
+}
+
+impl TagsManager {
+    pub async fn list_tag_rules(&self) -> Result<Vec<TagRule>> {
+        Ok(self.storage.load_vault_settings()?.tag_rules)
+    }
+
+    /// Adds a rule, or replaces the one with the same id.
+    pub async fn save_tag_rule(&self, mut rule: TagRule) -> Result<TagRule> {
+        compile(&rule.condition)?;
+        let mut settings = self.storage.load_vault_settings()?;
+        rule.tags = settings.tag_policy.normalize_all(rule.tags);
+        if rule.tags.is_empty() {
+            return Err(anyhow::anyhow!("A tag rule needs at least one tag"));
+        }
+
+        match settings.tag_rules.iter_mut().find(|r| !rule.id.is_empty() && r.id == rule.id) {
+            Some(existing) => *existing = rule.clone(),
+            None => {
+                rule.id = uuid::Uuid::new_v4().to_string();
+                settings.tag_rules.push(rule.clone());
+            }
+        }
+        self.storage.save_vault_settings(&settings)?;
+        Ok(rule)
+    }
+
+    pub async fn delete_tag_rule(&self, rule_id: &str) -> Result<bool> {
+        let mut settings = self.storage.load_vault_settings()?;
+        let before = settings.tag_rules.len();
+        settings.tag_rules.retain(|r| r.id != rule_id);
+        if settings.tag_rules.len() == before {
+            return Ok(false);
+        }
+        self.storage.save_vault_settings(&settings)?;
+        Ok(true)
+    }
+
+    /// Dry run: existing notes `rule` would add tags to, whether or not it is saved.
+    pub async fn preview_tag_rule(&self, rule: TagRule) -> Result<Vec<TagRulePreview>> {
+        compile(&rule.condition)?;
+        let rule = TagRule { enabled: true, ..rule };
+        let rules = [rule];
+        let rule_set = RuleSet::new(&rules);
+
+        let mut previews = Vec::new();
+        for file in self.storage.scan_existing_files()? {
+            if let Ok((metadata, content)) = self.storage.parse_note(&file.name) {
+                if metadata.deleted {
+                    continue;
+                }
+                let added: Vec<String> = missing_tags(&rule_set, &metadata, &content);
+                if !added.is_empty() {
+                    previews.push(TagRulePreview {
+                        note_id: metadata.id,
+                        title: metadata.title,
+                        added_tags: added,
+                        locked: metadata.locked,
+                    });
+                }
+            }
+        }
+        Ok(previews)
+    }
+
+    /// Runs every enabled rule over all notes outside the trash.
+    pub async fn apply_rules_to_vault(&self) -> Result<BulkResult> {
+        let settings = self.storage.load_vault_settings()?;
+        let rule_set = RuleSet::new(&settings.tag_rules);
+
+        let (result, changes) = self.rewrite_tags(false, |metadata, content| {
+            if metadata.deleted {
+                return None;
+            }
+            let added = missing_tags(&rule_set, metadata, content);
+            if added.is_empty() {
+                return None;
+            }
+            Some(settings.tag_policy.normalize_all(metadata.tags.iter().cloned().chain(added)))
+        })?;
+        self.storage.record_operation("apply_tag_rules", format!("Applied tag rules to {} notes", changes.len()), changes)?;
+        Ok(result)
+    }
+}
+
+fn missing_tags(rule_set: &RuleSet, metadata: &NoteMetadata, content: &str) -> Vec<String> {
+    let body = if metadata.encrypted { None } else { Some(content) };
+    rule_set.tags_for(metadata, body)
+        .into_iter()
+        .filter(|t| !metadata.tags.contains(t))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_vault;
+    use crate::models::{CreateNoteRequest, UpdateNoteRequest};
+
+    fn rule(condition: RuleCondition, tag: &str) -> TagRule {
+        TagRule {
+            id: String::new(),
+            name: tag.to_string(),
+            enabled: true,
+            condition,
+            tags: vec![tag.to_string()],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rules_tag_new_edited_and_existing_notes() {
+        let vault = test_vault();
+        let notes = &vault.notes;
+        let manager = vault.tags();
+
+        let existing = vault.create_note("Standup", Some("Blocked on JIRA-12"), &[]).await;
+
+        assert!(manager.save_tag_rule(rule(RuleCondition::TitleMatches { pattern: "(".to_string() }, "x")).await.is_err());
+        let jira = rule(RuleCondition::BodyMatches { pattern: r"JIRA-\d+".to_string() }, "jira");
+        let preview = manager.preview_tag_rule(jira.clone()).await.unwrap();
+        assert_eq!((preview.len(), preview[0].added_tags.clone()), (1, vec!["jira".to_string()]));
+
+        manager.save_tag_rule(jira).await.unwrap();
+        let meeting = manager.save_tag_rule(rule(RuleCondition::TitleMatches { pattern: "^Meeting".to_string() }, "meeting")).await.unwrap();
+        manager.save_tag_rule(rule(RuleCondition::FromTemplate { template: "daily".to_string() }, "journal")).await.unwrap();
+        assert_eq!(manager.list_tag_rules().await.unwrap().len(), 3);
+
+        let created = notes.create_note(CreateNoteRequest {
+            title: "Meeting notes".to_string(),
+            content: None,
+            tags: Some(vec!["work".to_string()]),
+            template: Some("daily".to_string()),
+        }).await.unwrap();
+        assert_eq!(created.tags, vec!["work", "meeting", "journal"]);
+
+        let edited = notes.update_note(UpdateNoteRequest {
+            id: created.id.clone(),
+            title: None,
+            content: Some("see JIRA-7".to_string()),
+            is_favorite: None,
+            tags: None,
+            properties: None,
+        }).await.unwrap().unwrap();
+        assert!(edited.tags.contains(&"jira".to_string()));
+
+        let applied = manager.apply_rules_to_vault().await.unwrap();
+        assert_eq!(applied.updated, vec![existing.id.clone()]);
+        assert!(manager.apply_rules_to_vault().await.unwrap().updated.is_empty());
+
+        // Removing a rule tag sticks, and later edits that already matched don't re-add it
+        let update = |content: &str, tags: Option<Vec<String>>| UpdateNoteRequest {
+            id: created.id.clone(),
+            title: None,
+            content: Some(content.to_string()),
+            is_favorite: None,
+            tags,
+            properties: None,
+        };
+        let untagged = notes.update_note(update("see JIRA-7", Some(vec!["work".to_string()]))).await.unwrap().unwrap();
+        assert_eq!(untagged.tags, vec!["work"]);
+        let edited = notes.update_note(update("see JIRA-7 and JIRA-8", None)).await.unwrap().unwrap();
+        assert_eq!(edited.tags, vec!["work"]);
+
+        assert!(manager.delete_tag_rule(&meeting.id).await.unwrap());
+        assert!(!manager.delete_tag_rule(&meeting.id).await.unwrap());
+    }
+}
@@ -0,0 +1,157 @@
+use super::{note_tags, TagsManager};
+use crate::models::TagSuggestion;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+// Share of the score that comes from co-occurrence when the note already has tags
+const COOCCURRENCE_WEIGHT: f64 = 0.3;
+const MIN_TERM_LEN: usize = 3;
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "had", "her", "was", "one",
+    "our", "out", "has", "have", "his", "how", "its", "may", "new", "now", "see", "who", "did", "get",
+    "this", "that", "with", "from", "they", "will", "would", "there", "their", "what", "about", "which",
+    "when", "your", "into", "than", "then", "them", "these", "some", "been", "were", "also", "just",
+];
+
+// Terms of one note and the tags it carries
+struct Document {
+    id: String,
+    tags: Vec<String>,
+    terms: HashMap<String, f64>,
+}
+
+impl TagsManager {
+    /// Ranks existing tags for a note by how close its words are to the notes
+    /// carrying each tag, blended with how often each tag appears alongside the
+    /// note's current tags. Everything is computed from the local vault.
+    pub async fn suggest_tags(&self, note_id: &str, limit: usize) -> Result<Vec<TagSuggestion>> {
+        let policy = self.storage.load_vault_settings()?.tag_policy;
+        let mut documents = Vec::new();
+        for file in self.storage.scan_existing_files()? {
+            if let Ok((metadata, content)) = self.storage.parse_note(&file.name) {
+                if metadata.deleted {
+                    continue;
+                }
+                // Encrypted notes contribute their title only
+                let body = if metadata.encrypted { "" } else { content.as_str() };
+                documents.push(Document {
+                    id: metadata.id.clone(),
+                    tags: note_tags(&policy, &metadata, &content),
+                    terms: term_counts(&format!("{}\n{}", metadata.title, body)),
+                });
+            }
+        }
+
+        let target = match documents.iter().position(|d| d.id == note_id) {
+            Some(index) => documents.swap_remove(index),
+            None => return Err(anyhow::anyhow!("Note not found")),
+        };
+
+        // Inverse document frequency over the whole vault, the note itself included
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+        for document in documents.iter().chain(std::iter::once(&target)) {
+            for term in document.terms.keys() {
+                *document_frequency.entry(term.as_str()).or_insert(0) += 1;
+            }
+        }
+        let total = (documents.len() + 1) as f64;
+        let idf = |term: &str| (total / (1.0 + document_frequency.get(term).copied().unwrap_or(0) as f64)).ln() + 1.0;
+        let weigh = |terms: &HashMap<String, f64>| -> HashMap<String, f64> {
+            terms.iter().map(|(term, count)| (term.clone(), count * idf(term))).collect()
+        };
+
+        let target_vector = weigh(&target.terms);
+        let target_tags: HashSet<&String> = target.tags.iter().collect();
+
+        // Term profile and note count per tag, from the other notes
+        let mut profiles: HashMap<&String, HashMap<String, f64>> = HashMap::new();
+        let mut tag_notes: HashMap<&String, usize> = HashMap::new();
+        let mut together: HashMap<(&String, &String), usize> = HashMap::new();
+        for document in &documents {
+            for tag in &document.tags {
+                *tag_notes.entry(tag).or_insert(0) += 1;
+                let profile = profiles.entry(tag).or_default();
+                for (term, count) in &document.terms {
+                    *profile.entry(term.clone()).or_insert(0.0) += count;
+                }
+                for existing in document.tags.iter().filter(|t| target_tags.contains(t)) {
+                    if existing != tag {
+                        *together.entry((existing, tag)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let mut suggestions: Vec<TagSuggestion> = profiles.iter()
+            .filter(|(tag, _)| !target_tags.contains(*tag))
+            .map(|(tag, profile)| {
+                let term_score = cosine(&target_vector, &weigh(profile));
+                // Average of P(tag | existing tag) over the note's tags
+                let cooccurrence_score = if target_tags.is_empty() {
+                    0.0
+                } else {
+                    target_tags.iter()
+                        .map(|existing| {
+                            let with = together.get(&(*existing, *tag)).copied().unwrap_or(0) as f64;
+                            let base = tag_notes.get(existing).copied().unwrap_or(0) as f64;
+                            if base > 0.0 { with / base } else { 0.0 }
+                        })
+                        .sum::<f64>() / target_tags.len() as f64
+                };
+                let score = if target_tags.is_empty() {
+                    term_score
+                } else {
+                    (1.0 - COOCCURRENCE_WEIGHT) * term_score + COOCCURRENCE_WEIGHT * cooccurrence_score
+                };
+                TagSuggestion { name: (*tag).clone(), score, term_score, cooccurrence_score }
+            })
+            .filter(|s| s.score > 0.0)
+            .collect();
+
+        suggestions.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+        suggestions.truncate(limit);
+        Ok(suggestions)
+    }
+}
+
+fn term_counts(text: &str) -> HashMap<String, f64> {
+    let mut counts = HashMap::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+        if word.chars().count() < MIN_TERM_LEN || word.chars().all(|c| c.is_ascii_digit()) || STOP_WORDS.contains(&word.as_str()) {
+            continue;
+        }
+        *counts.entry(word).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+fn cosine(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a.iter().filter_map(|(term, x)| b.get(term).map(|y| x * y)).sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 { 0.0 } else { dot / denominator }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::test_vault;
+
+    #[tokio::test]
+    async fn test_suggestions_rank_by_terms_and_cooccurrence() {
+        let vault = test_vault();
+        let manager = vault.tags();
+
+        vault.create_note("Sourdough", Some("flour water starter bake loaf"), &["baking", "food"]).await;
+        vault.create_note("Focaccia", Some("flour olive oil bake tray"), &["baking", "food"]).await;
+        vault.create_note("Quarterly review", Some("budget forecast revenue meeting"), &["work"]).await;
+        let target = vault.create_note("Rye bread", Some("rye flour starter bake"), &["food"]).await;
+
+        let suggestions = manager.suggest_tags(&target.id, 5).await.unwrap();
+        assert_eq!(suggestions[0].name, "baking");
+        assert!(suggestions[0].cooccurrence_score > 0.99);
+        assert!(suggestions.iter().all(|s| s.name != "food"));
+        // Nothing in common with the note
+        assert!(suggestions.iter().all(|s| s.name != "work"));
+    }
+}
@@ -0,0 +1,45 @@
+use crate::attachments::AttachmentsManager;
+use crate::models::{CreateNoteRequest, Note};
+use crate::notes::NotesManager;
+use crate::storage::FileStorageManager;
+use crate::tags::TagsManager;
+use std::path::Path;
+use tempfile::TempDir;
+
+// A vault in a temporary directory, removed when dropped
+pub struct TestVault {
+    dir: TempDir,
+    pub storage: FileStorageManager,
+    pub notes: NotesManager,
+}
+
+pub fn test_vault() -> TestVault {
+    let dir = TempDir::new().unwrap();
+    let storage = FileStorageManager::new(dir.path().to_path_buf()).unwrap();
+    let notes = NotesManager::new(storage.clone());
+    TestVault { dir, storage, notes }
+}
+
+impl TestVault {
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn tags(&self) -> TagsManager {
+        TagsManager::new(self.storage.clone())
+    }
+
+    pub fn attachments(&self) -> AttachmentsManager {
+        AttachmentsManager::new(self.storage.clone())
+    }
+
+    // No content keeps the default body of new notes
+    pub async fn create_note(&self, title: &str, content: Option<&str>, tags: &[&str]) -> Note {
+        self.notes.create_note(CreateNoteRequest {
+            title: title.to_string(),
+            content: content.map(String::from),
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            template: None,
+        }).await.unwrap()
+    }
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+// Hidden folder inside the notes directory holding per-vault state
+pub const VAULT_DIR_NAME: &str = ".xnote";
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct VaultSettings {
+    // Board definitions keyed by the front matter field they group on
+    #[serde(default)]
+    pub boards: BTreeMap<String, BoardConfig>,
+    #[serde(default)]
+    pub pins: NoteLists,
+    // Manual order for NoteSort::Manual
+    #[serde(default)]
+    pub order: NoteLists,
+    #[serde(default)]
+    pub history: HistorySettings,
+    // Registered tags keyed by name, so a tag can exist before any note uses it
+    #[serde(default)]
+    pub tags: BTreeMap<String, TagDefinition>,
+    #[serde(default)]
+    pub tag_policy: TagPolicy,
+    // Applied in order whenever a note is created or edited
+    #[serde(default)]
+    pub tag_rules: Vec<TagRule>,
+}
+
+// Note ids in display order for the main list and for each tag's list
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct NoteLists {
+    #[serde(default)]
+    pub global: Vec<String>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct BoardConfig {
+    pub states: Vec<String>,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            states: vec!["Todo".to_string(), "Doing".to_string(), "Done".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TagDefinition {
+    // Stays the same when the tag is renamed
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created: DateTime<Utc>,
+}
+
+impl Default for TagDefinition {
+    fn default() -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            color: None,
+            icon: None,
+            description: None,
+            created: Utc::now(),
+        }
+    }
+}
+
+// Clean-up applied to tag names whenever notes are tagged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", default)]
+pub struct TagPolicy {
+    // Strips surrounding whitespace, also around each `/` level
+    pub trim: bool,
+    // Composes NFD names from macOS file systems into NFC
+    pub unicode_nfc: bool,
+    pub case_fold: bool,
+    pub inline_tags: InlineTagMode,
+}
+
+impl Default for TagPolicy {
+    fn default() -> Self {
+        Self {
+            trim: true,
+            unicode_nfc: true,
+            case_fold: false,
+            inline_tags: InlineTagMode::Count,
+        }
+    }
+}
+
+// What `#tags` written in a note body mean
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InlineTagMode {
+    Ignore,
+    // Listed and filtered like front matter tags, but not written anywhere
+    Count,
+    // Also copied into front matter `tags` whenever the note is saved
+    Sync,
+}
+
+impl TagPolicy {
+    pub fn normalize(&self, tag: &str) -> String {
+        let mut tag = if self.trim {
+            tag.split('/').map(str::trim).collect::<Vec<_>>().join("/")
+        } else {
+            tag.to_string()
+        };
+        if self.unicode_nfc {
+            tag = tag.nfc().collect();
+        }
+        if self.case_fold {
+            tag = tag.to_lowercase();
+        }
+        tag
+    }
+    
+    /// Normalizes every tag, dropping empty names and duplicates.
+    pub fn normalize_all<I: IntoIterator<Item = String>>(&self, tags: I) -> Vec<String> {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = self.normalize(&tag);
+            if !tag.is_empty() && !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+        normalized
+    }
+}
+
+// Adds `tags` to every note matching `condition`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TagRule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub condition: RuleCondition,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleCondition {
+    // Regex searched in the title
+    TitleMatches { pattern: String },
+    // Regex searched in the body; encrypted bodies never match
+    BodyMatches { pattern: String },
+    FromTemplate { template: String },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// Local snapshots kept under .xnote/history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", default)]
+pub struct HistorySettings {
+    pub enabled: bool,
+    // At most one snapshot per note in this window
+    pub min_interval_minutes: i64,
+    pub max_versions: usize,
+    // Snapshots older than this are pruned; 0 keeps them forever
+    pub max_age_days: i64,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_interval_minutes: 5,
+            max_versions: 100,
+            max_age_days: 90,
+        }
+    }
+}
+
+impl VaultSettings {
+    pub fn load(notes_directory: &Path) -> Result<Self> {
+        let path = settings_path(notes_directory);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(&path)
+            .context("Failed to read vault settings")?;
+        
+        serde_json::from_str(&content)
+            .context("Failed to parse vault settings")
+    }
+    
+    pub fn save(&self, notes_directory: &Path) -> Result<()> {
+        let vault_dir = vault_directory(notes_directory);
+        fs::create_dir_all(&vault_dir)
+            .context("Failed to create vault directory")?;
+        
+        let content = serde_json::to_string_pretty(self)
+            .context("Failed to serialize vault settings")?;
+        
+        // Atomic write, same as notes
+        let path = settings_path(notes_directory);
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, content)
+            .context("Failed to write vault settings")?;
+        fs::rename(&temp_path, &path)
+            .context("Failed to write vault settings")
+    }
+    
+    pub fn board(&self, status_field: &str) -> BoardConfig {
+        self.boards.get(status_field).cloned().unwrap_or_default()
+    }
+    
+    /// Pinned ids for a tag view, or the global list when `tag` is None.
+    pub fn pinned(&self, tag: Option<&str>) -> &[String] {
+        self.pins.list(tag)
+    }
+    
+    /// Tag name for a registry id; anything else is taken to be a name already.
+    pub fn tag_name(&self, id_or_name: &str) -> String {
+        self.tags.iter()
+            .find(|(_, tag)| tag.id == id_or_name)
+            .map(|(name, _)| name.clone())
+            .unwrap_or_else(|| id_or_name.to_string())
+    }
+    
+    pub fn pinned_mut(&mut self, tag: Option<&str>) -> &mut Vec<String> {
+        self.pins.list_mut(tag)
+    }
+}
+
+impl NoteLists {
+    pub fn list(&self, tag: Option<&str>) -> &[String] {
+        match tag {
+            Some(tag) => self.tags.get(tag).map(|ids| ids.as_slice()).unwrap_or(&[]),
+            None => &self.global,
+        }
+    }
+    
+    pub fn list_mut(&mut self, tag: Option<&str>) -> &mut Vec<String> {
+        match tag {
+            Some(tag) => self.tags.entry(tag.to_string()).or_default(),
+            None => &mut self.global,
+        }
+    }
+    
+    // Drops a note from every list, and lists left empty; true if anything changed
+    pub fn forget(&mut self, note_id: &str) -> bool {
+        let mut changed = false;
+        for ids in std::iter::once(&mut self.global).chain(self.tags.values_mut()) {
+            let before = ids.len();
+            ids.retain(|id| id != note_id);
+            changed |= ids.len() != before;
+        }
+        self.tags.retain(|_, ids| !ids.is_empty());
+        changed
+    }
+}
+
+pub fn vault_directory(notes_directory: &Path) -> PathBuf {
+    notes_directory.join(VAULT_DIR_NAME)
+}
+
+fn settings_path(notes_directory: &Path) -> PathBuf {
+    vault_directory(notes_directory).join(SETTINGS_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_missing_settings_use_defaults() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = VaultSettings::load(temp_dir.path()).unwrap();
+        
+        assert!(settings.boards.is_empty());
+        assert_eq!(settings.board("status").states, vec!["Todo", "Doing", "Done"]);
+    }
+    
+    #[test]
+    fn test_settings_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut settings = VaultSettings::default();
+        settings.boards.insert("stage".to_string(), BoardConfig {
+            states: vec!["Draft".to_string(), "Review".to_string()],
+        });
+        settings.save(temp_dir.path()).unwrap();
+        
+        assert!(temp_dir.path().join(".xnote").join("settings.json").exists());
+        let loaded = VaultSettings::load(temp_dir.path()).unwrap();
+        assert_eq!(loaded.board("stage").states, vec!["Draft", "Review"]);
+    }
+}
@@ -1,10 +1,12 @@
 import { defineStore } from 'pinia'
 import { ref } from 'vue'
-import type { Tag } from '@/types'
+import type { Tag, UpdateTagRequest } from '@/types'
 import { api } from '@/utils/api'
 
 export const useTagsStore = defineStore('tags', () => {
+  // Flat list of every tag; tagTree keeps the nesting
   const tags = ref<Tag[]>([])
+  const tagTree = ref<Tag[]>([])
   const selectedTag = ref<string>('All Notes')
   const loading = ref(false)
   const error = ref<string | null>(null)
@@ -20,7 +22,8 @@ export const useTagsStore = defineStore('tags', () => {
     try {
       loading.value = true
       error.value = null
-      tags.value = await api.getAllTags()
+      tagTree.value = await api.getAllTags()
+      tags.value = flattenTags(tagTree.value)
     } catch (err) {
       error.value = err instanceof Error ? err.message : 'Failed to load tags'
     } finally {
@@ -44,7 +47,10 @@ export const useTagsStore = defineStore('tags', () => {
   async function deleteTag(tagId: string): Promise<boolean> {
     try {
       error.value = null
-      const success = await api.deleteTag(tagId)
+      const registered = tags.value.some(t => t.id === tagId && t.created_at)
+      const result = await api.deleteTag(tagId)
+      // Registered tags can be deleted without any note carrying them
+      const success = result.updated.length > 0 || registered
       if (success) {
         // Reload tags to get updated list
         await loadTags()
@@ -59,18 +65,32 @@ export const useTagsStore = defineStore('tags', () => {
   async function renameTag(tagId: string, newName: string): Promise<Tag | null> {
     try {
       error.value = null
-      const tag = await api.renameTag(tagId, newName)
-      if (tag) {
-        // Reload tags to get updated list
-        await loadTags()
+      const registered = tags.value.some(t => t.id === tagId && t.created_at)
+      const result = await api.renameTag(tagId, newName)
+      if (result.updated.length === 0 && !registered) {
+        return null
       }
-      return tag
+      // Reload tags to get updated list
+      await loadTags()
+      return tags.value.find(t => t.name === newName) ?? null
     } catch (err) {
       error.value = err instanceof Error ? err.message : 'Failed to rename tag'
       return null
     }
   }
 
+  async function updateTag(request: UpdateTagRequest): Promise<Tag | null> {
+    try {
+      error.value = null
+      const tag = await api.updateTag(request)
+      await loadTags()
+      return tag
+    } catch (err) {
+      error.value = err instanceof Error ? err.message : 'Failed to update tag'
+      return null
+    }
+  }
+
   async function addTagToNote(noteId: string, tagName: string): Promise<Tag | null> {
     try {
       error.value = null
@@ -122,10 +142,10 @@ export const useTagsStore = defineStore('tags', () => {
   async function cleanupUnusedTags(): Promise<number> {
     try {
       error.value = null
-      const deletedCount = await api.cleanupUnusedTags()
+      const cleanup = await api.cleanupUnusedTags(false)
       // Reload tags to get updated list
       await loadTags()
-      return deletedCount
+      return cleanup.tags.length
     } catch (err) {
       error.value = err instanceof Error ? err.message : 'Failed to cleanup unused tags'
       return 0
@@ -136,8 +156,13 @@ export const useTagsStore = defineStore('tags', () => {
     selectedTag.value = tagName
   }
 
+  function flattenTags(nodes: Tag[]): Tag[] {
+    return nodes.flatMap(tag => [tag, ...flattenTags(tag.children ?? [])])
+  }
+
   return {
     tags,
+    tagTree,
     selectedTag,
     loading,
     error,
@@ -145,6 +170,7 @@ export const useTagsStore = defineStore('tags', () => {
     createTag,
     deleteTag,
     renameTag,
+    updateTag,
     addTagToNote,
     removeTagFromNote,
     getNoteTags,
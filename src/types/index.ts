@@ -8,6 +8,8 @@ export interface Note {
   is_favorite: boolean
   is_deleted: boolean
   tags: string[]
+  // `#tags` written in the body
+  inline_tags: string[]
   has_attachments: boolean
   attachments: string[]
 }
@@ -16,12 +18,96 @@ export interface Tag {
   id: string
   name: string
   note_count: number
+  // Includes notes tagged with nested tags such as `work/clientA`
+  total_count: number
+  archived_count?: number
+  trashed_count?: number
+  color?: string | null
+  icon?: string | null
+  description?: string | null
+  created_at?: string | null
+  children: Tag[]
 }
 
+export interface TagPolicy {
+  trim: boolean
+  unicode_nfc: boolean
+  case_fold: boolean
+  inline_tags: InlineTagMode
+}
+
+// What `#tags` written in a note body mean
+export type InlineTagMode = 'ignore' | 'count' | 'sync'
+
+export interface TagRename {
+  from: string
+  to: string
+  note_count: number
+}
+
+export interface TagNormalization {
+  renames: TagRename[]
+  updated: string[]
+  skipped_locked: string[]
+  applied: boolean
+}
+
+// Tags dropped from the vault settings, or only listed in a dry run
+export interface TagCleanup {
+  tags: string[]
+  applied: boolean
+}
+
+export type RuleCondition =
+  | { type: 'title_matches'; pattern: string }
+  | { type: 'body_matches'; pattern: string }
+  | { type: 'from_template'; template: string }
+
+export interface TagRule {
+  id: string
+  name: string
+  enabled: boolean
+  condition: RuleCondition
+  tags: string[]
+}
+
+export interface TagRulePreview {
+  note_id: string
+  title: string
+  added_tags: string[]
+  locked: boolean
+}
+
+export interface TagSuggestion {
+  name: string
+  score: number
+  term_score: number
+  cooccurrence_score: number
+}
+
+export interface UpdateTagRequest {
+  id: string
+  color?: string
+  icon?: string
+  description?: string
+}
+
+export interface BulkResult {
+  updated: string[]
+  skipped_locked: string[]
+}
+
+// Rejection value of commands that edit notes
+export type CommandError =
+  | { kind: 'note_locked'; note_id: string; message: string }
+  | { kind: 'failed'; message: string }
+
 export interface CreateNoteRequest {
   title: string
   content?: string
   tags?: string[]
+  // Recorded on the note so tag rules can match it
+  template?: string
 }
 
 export interface UpdateNoteRequest {
@@ -32,9 +118,62 @@ export interface UpdateNoteRequest {
   tags?: string[]
 }
 
+export type NoteSort = 'modified' | 'created' | 'title' | 'manual'
+
 export interface SearchRequest {
   query: string
   tag_filter?: string
+  sort?: NoteSort
+}
+
+export interface Attachment {
+  // Relative to the notes directory, as notes link to it
+  path: string
+  file_name: string
+  size: number
+  mime_type: string
+  hash: string
+  width?: number | null
+  height?: number | null
+  created_at: string
+  reference_count: number
+}
+
+export interface AttachmentReference {
+  note_id: string
+  title: string
+  deleted: boolean
+}
+
+export interface AttachmentInfo extends Attachment {
+  referenced_by: AttachmentReference[]
+}
+
+// Attachments moved to the attachment trash, or only listed in a dry run
+export interface AttachmentCleanup {
+  attachments: string[]
+  bytes: number
+  applied: boolean
+}
+
+export type AttachmentSort = 'name' | 'size' | 'created' | 'references'
+
+export interface AttachmentFilter {
+  query?: string
+  // Full type or a prefix such as `image/`
+  mime_type?: string
+  referenced?: boolean
+  sort_by?: AttachmentSort
+  descending?: boolean
+}
+
+// Unsaved editor content newer than the note on disk
+export interface DraftRecovery {
+  note_id: string
+  note_title: string
+  content: string
+  saved_at: string
+  note_modified_at: string
 }
 
 export type ViewMode = 'view' | 'edit' | 'split'
@@ -1,26 +1,30 @@
 import { invoke } from '@tauri-apps/api/tauri'
-import type { Note, Tag, CreateNoteRequest, UpdateNoteRequest, SearchRequest } from '@/types'
+import type { Note, NoteSort, Tag, BulkResult, UpdateTagRequest, TagPolicy, TagNormalization, TagCleanup, TagRule, TagRulePreview, TagSuggestion, Attachment, AttachmentInfo, AttachmentFilter, AttachmentCleanup, DraftRecovery, CreateNoteRequest, UpdateNoteRequest, SearchRequest } from '@/types'
 
 export const api = {
   // Notes
-  async getAllNotes(): Promise<Note[]> {
-    return await invoke('get_all_notes')
+  async getAllNotes(sort?: NoteSort): Promise<Note[]> {
+    return await invoke('get_all_notes', { sort })
   },
 
-  async getFavorites(): Promise<Note[]> {
-    return await invoke('get_favorites')
+  async getFavorites(sort?: NoteSort): Promise<Note[]> {
+    return await invoke('get_favorites', { sort })
   },
 
-  async getUntagged(): Promise<Note[]> {
-    return await invoke('get_untagged')
+  async getUntagged(sort?: NoteSort): Promise<Note[]> {
+    return await invoke('get_untagged', { sort })
   },
 
-  async getTrash(): Promise<Note[]> {
-    return await invoke('get_trash')
+  async getTrash(sort?: NoteSort): Promise<Note[]> {
+    return await invoke('get_trash', { sort })
   },
 
-  async getNotesByTag(tagName: string): Promise<Note[]> {
-    return await invoke('get_notes_by_tag', { tagName })
+  async getNotesByTag(tagName: string, includeDescendants = false, sort?: NoteSort): Promise<Note[]> {
+    return await invoke('get_notes_by_tag', { tagName, includeDescendants, sort })
+  },
+
+  async setManualOrder(noteIds: string[], tag?: string): Promise<string[]> {
+    return await invoke('set_manual_order', { noteIds, tag })
   },
 
   async getNote(id: string): Promise<Note | null> {
@@ -51,6 +55,23 @@ export const api = {
     return await invoke('search_notes', { request })
   },
 
+  // Drafts
+  async saveDraft(noteId: string, content: string): Promise<boolean> {
+    return await invoke('save_draft', { noteId, content })
+  },
+
+  async getRecoverableDrafts(): Promise<DraftRecovery[]> {
+    return await invoke('get_recoverable_drafts')
+  },
+
+  async recoverDraft(noteId: string): Promise<Note | null> {
+    return await invoke('recover_draft', { noteId })
+  },
+
+  async discardDraft(noteId: string): Promise<void> {
+    return await invoke('discard_draft', { noteId })
+  },
+
   // Tags
   async getAllTags(): Promise<Tag[]> {
     return await invoke('get_all_tags')
@@ -60,14 +81,59 @@ export const api = {
     return await invoke('create_tag', { name })
   },
 
-  async deleteTag(tagId: string): Promise<boolean> {
+  async deleteTag(tagId: string): Promise<BulkResult> {
     return await invoke('delete_tag', { tagId })
   },
 
-  async renameTag(tagId: string, newName: string): Promise<Tag | null> {
+  async renameTag(tagId: string, newName: string): Promise<BulkResult> {
     return await invoke('rename_tag', { tagId, newName })
   },
 
+  async updateTag(request: UpdateTagRequest): Promise<Tag> {
+    return await invoke('update_tag', { request })
+  },
+
+  async mergeTags(sources: string[], target: string): Promise<BulkResult> {
+    return await invoke('merge_tags', { sources, target })
+  },
+
+  async normalizeTags(dryRun: boolean): Promise<TagNormalization> {
+    return await invoke('normalize_tags', { dryRun })
+  },
+
+  async syncInlineTags(): Promise<BulkResult> {
+    return await invoke('sync_inline_tags')
+  },
+
+  // Tag rules
+  async listTagRules(): Promise<TagRule[]> {
+    return await invoke('list_tag_rules')
+  },
+
+  async saveTagRule(rule: TagRule): Promise<TagRule> {
+    return await invoke('save_tag_rule', { rule })
+  },
+
+  async deleteTagRule(ruleId: string): Promise<boolean> {
+    return await invoke('delete_tag_rule', { ruleId })
+  },
+
+  async previewTagRule(rule: TagRule): Promise<TagRulePreview[]> {
+    return await invoke('preview_tag_rule', { rule })
+  },
+
+  async applyRulesToVault(): Promise<BulkResult> {
+    return await invoke('apply_rules_to_vault')
+  },
+
+  async getTagPolicy(): Promise<TagPolicy> {
+    return await invoke('get_tag_policy')
+  },
+
+  async setTagPolicy(policy: TagPolicy): Promise<void> {
+    return await invoke('set_tag_policy', { policy })
+  },
+
   async addTagToNote(noteId: string, tagName: string): Promise<Tag> {
     return await invoke('add_tag_to_note', { noteId, tagName })
   },
@@ -84,8 +150,25 @@ export const api = {
     return await invoke('search_tags', { query })
   },
 
-  async cleanupUnusedTags(): Promise<number> {
-    return await invoke('cleanup_unused_tags')
+  async suggestTags(noteId: string, limit?: number): Promise<TagSuggestion[]> {
+    return await invoke('suggest_tags', { noteId, limit })
+  },
+
+  async cleanupUnusedTags(dryRun = true): Promise<TagCleanup> {
+    return await invoke('cleanup_unused_tags', { dryRun })
+  },
+
+  // Attachments
+  async listVaultAttachments(filter?: AttachmentFilter): Promise<Attachment[]> {
+    return await invoke('list_vault_attachments', { filter })
+  },
+
+  async getAttachmentInfo(path: string): Promise<AttachmentInfo | null> {
+    return await invoke('get_attachment_info', { path })
+  },
+
+  async cleanupUnreferencedAttachments(dryRun = true): Promise<AttachmentCleanup> {
+    return await invoke('cleanup_unreferenced_attachments', { dryRun })
   },
   
   // Configuration